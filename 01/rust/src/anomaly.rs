@@ -0,0 +1,360 @@
+//! Suspicious readings in a depth profile.
+
+use std::{fmt::Display, io::BufRead, ops::Range};
+
+use anyhow::Result;
+
+use crate::{
+    reading::Reading,
+    sweep::{read_measurements, Recent},
+};
+
+/// What to flag, each check being off when `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Thresholds {
+    /// Change between consecutive readings above which they are flagged
+    pub jump: Option<u128>,
+    /// Number of identical readings in a row making a flat line
+    pub flat: Option<usize>,
+    /// Readings too far from the ones before them
+    pub outlier: Option<Outlier>,
+}
+
+/// Flags readings whose z-score against the `window` readings before them
+/// is above `z`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outlier {
+    pub window: usize,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason<T> {
+    Jump { from: T, to: T },
+    FlatLine { value: T },
+    Outlier { value: T, mean: f64, z: f64 },
+}
+
+impl<T: Display> Display for Reason<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Jump { from, to } => write!(f, "jump from {} to {}", from, to),
+            Reason::FlatLine { value } => write!(f, "flat line at {}", value),
+            Reason::Outlier { value, mean, z } => write!(
+                f,
+                "{} is {:.1} standard deviations from the rolling mean {:.1}",
+                value, z, mean
+            ),
+        }
+    }
+}
+
+/// Suspicious readings at `range` of the measurement indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly<T> {
+    pub range: Range<usize>,
+    pub reason: Reason<T>,
+}
+
+impl<T: Display> Display for Anomaly<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}..{}: {}",
+            self.range.start, self.range.end, self.reason
+        )
+    }
+}
+
+/// The last readings, to compare the next one with.
+#[derive(Debug, Clone)]
+struct Rolling<T> {
+    size: usize,
+    recent: Recent<T>,
+}
+
+/// Signed distance from `from` to `to`, exact for close readings however
+/// large they are.
+fn offset<T: Reading>(from: T, to: T) -> f64 {
+    let distance = from.abs_diff(to) as f64;
+    if to < from {
+        -distance
+    } else {
+        distance
+    }
+}
+
+impl<T: Reading> Rolling<T> {
+    fn new(size: usize) -> Self {
+        assert!(size > 1, "Outlier window must be at least 2");
+        Self {
+            size,
+            recent: Recent::new(size),
+        }
+    }
+
+    /// The rolling mean and the z-score of `measurement` against it, once
+    /// the window is full. A constant window has no spread, leaving it to
+    /// the flat line check.
+    ///
+    /// Readings are taken relative to the first one of the window, so that
+    /// the spread of large readings is not lost to rounding.
+    fn score(&self, measurement: T) -> Option<(f64, f64)> {
+        if !self.recent.is_full() {
+            return None;
+        }
+        let first = self.recent.get(0);
+        let n = self.size as f64;
+        let mean = self
+            .recent
+            .range(..)
+            .map(|&m| offset(first, m))
+            .sum::<f64>()
+            / n;
+        let variance = self
+            .recent
+            .range(..)
+            .map(|&m| (offset(first, m) - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        if variance == 0. {
+            return None;
+        }
+        let z = (offset(first, measurement) - mean).abs() / variance.sqrt();
+        Some((first.to_f64() + mean, z))
+    }
+
+    fn push(&mut self, measurement: T) {
+        self.recent.push(measurement);
+    }
+}
+
+/// Checks measurements for anomalies one at a time.
+///
+/// Anomalies are reported once they are known, so a flat line only when it
+/// ends, or by `finish`.
+#[derive(Debug, Clone)]
+pub struct Detector<T> {
+    thresholds: Thresholds,
+    index: usize,
+    previous: Option<T>,
+    run_start: usize,
+    rolling: Option<Rolling<T>>,
+    found: Vec<Anomaly<T>>,
+    lines: usize,
+}
+
+impl<T: Reading> Detector<T> {
+    /// Panics if a flat line or outlier window is shorter than 2.
+    pub fn new(thresholds: Thresholds) -> Self {
+        if let Some(flat) = thresholds.flat {
+            assert!(flat > 1, "Flat line must be at least 2 readings");
+        }
+        Self {
+            thresholds,
+            index: 0,
+            previous: None,
+            run_start: 0,
+            rolling: thresholds.outlier.map(|o| Rolling::new(o.window)),
+            found: Vec::new(),
+            lines: 0,
+        }
+    }
+
+    pub fn push(&mut self, measurement: T) {
+        let i = self.index;
+        if let Some(previous) = self.previous {
+            match self.thresholds.jump {
+                Some(jump) if previous.abs_diff(measurement) > jump => {
+                    self.found.push(Anomaly {
+                        range: i - 1..i + 1,
+                        reason: Reason::Jump {
+                            from: previous,
+                            to: measurement,
+                        },
+                    });
+                }
+                _ => {}
+            }
+            if previous != measurement {
+                self.end_run();
+                self.run_start = i;
+            }
+        }
+
+        if let (Some(rolling), Some(outlier)) = (&mut self.rolling, self.thresholds.outlier) {
+            if let Some((mean, z)) = rolling.score(measurement) {
+                if z > outlier.z {
+                    self.found.push(Anomaly {
+                        range: i..i + 1,
+                        reason: Reason::Outlier {
+                            value: measurement,
+                            mean,
+                            z,
+                        },
+                    });
+                }
+            }
+            rolling.push(measurement);
+        }
+
+        self.previous = Some(measurement);
+        self.index += 1;
+    }
+
+    fn end_run(&mut self) {
+        match (self.thresholds.flat, self.previous) {
+            (Some(flat), Some(value)) if self.index - self.run_start >= flat => {
+                self.found.push(Anomaly {
+                    range: self.run_start..self.index,
+                    reason: Reason::FlatLine { value },
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Feeds the measurements read from `reader`, like `Sweep::read`.
+    pub fn read(&mut self, reader: impl BufRead) -> Result<()> {
+        let mut lines = self.lines;
+        let result = read_measurements(reader, &mut lines, |m, _| {
+            self.push(m);
+            Ok(())
+        });
+        self.lines = lines;
+        result
+    }
+
+    /// Takes the anomalies found so far.
+    pub fn take(&mut self) -> Vec<Anomaly<T>> {
+        std::mem::take(&mut self.found)
+    }
+
+    /// The anomalies not taken yet, including a flat line at the end.
+    pub fn finish(mut self) -> Vec<Anomaly<T>> {
+        self.end_run();
+        self.found
+    }
+}
+
+/// Anomalies of the whole series, in the order they are detected.
+pub fn anomalies<T: Reading>(measurements: &[T], thresholds: Thresholds) -> Vec<Anomaly<T>> {
+    let mut detector = Detector::new(thresholds);
+    for &m in measurements {
+        detector.push(m);
+    }
+    detector.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump() {
+        let found = anomalies(
+            &[199, 200, 208, 210, 200, 207, 240, 269, 260, 263],
+            Thresholds {
+                jump: Some(25),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            found,
+            vec![
+                Anomaly {
+                    range: 5..7,
+                    reason: Reason::Jump { from: 207, to: 240 }
+                },
+                Anomaly {
+                    range: 6..8,
+                    reason: Reason::Jump { from: 240, to: 269 }
+                },
+            ]
+        );
+        assert_eq!(found[0].to_string(), "5..7: jump from 207 to 240");
+    }
+
+    #[test]
+    fn test_flat_line() {
+        let thresholds = Thresholds {
+            flat: Some(3),
+            ..Default::default()
+        };
+        let found = anomalies(&[1, 5, 5, 5, 2, 2, 7, 7, 7, 7], thresholds);
+        assert_eq!(
+            found.iter().map(|a| a.range.clone()).collect::<Vec<_>>(),
+            vec![1..4, 6..10]
+        );
+        assert_eq!(found[1].reason, Reason::FlatLine { value: 7 });
+
+        let mut detector = Detector::new(thresholds);
+        for m in [4, 4, 4, 4] {
+            detector.push(m);
+        }
+        assert!(detector.take().is_empty());
+        detector.push(3);
+        assert_eq!(detector.take()[0].range, 0..4);
+        assert!(detector.finish().is_empty());
+    }
+
+    #[test]
+    fn test_outlier() {
+        let found = anomalies(
+            &[10, 12, 10, 12, 30, 11, 12, 11],
+            Thresholds {
+                outlier: Some(Outlier { window: 4, z: 3. }),
+                ..Default::default()
+            },
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].range, 4..5);
+        match found[0].reason {
+            Reason::Outlier { value, mean, z } => {
+                assert_eq!(value, 30);
+                assert_eq!(mean, 11.);
+                assert_eq!(z, 19.);
+            }
+            ref r => panic!("Unexpected reason {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_outlier_large_readings() {
+        let thresholds = Thresholds {
+            outlier: Some(Outlier { window: 4, z: 3. }),
+            ..Default::default()
+        };
+        for base in [0, 1_000_000_000, 100_000_000_000, i64::MAX - 20] {
+            let mut measurements = (0..40).map(|i| base + i % 2).collect::<Vec<i64>>();
+            measurements.push(base + 10);
+            let found = anomalies(&measurements, thresholds);
+            assert_eq!(
+                found.iter().map(|a| a.range.clone()).collect::<Vec<_>>(),
+                vec![40..41],
+                "base {}",
+                base
+            );
+        }
+
+        let base = 10i128.pow(30);
+        let found = anomalies(&[base, base + 2, base, base + 2, base + 1], thresholds);
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_read() -> Result<()> {
+        let mut detector = Detector::<u64>::new(Thresholds {
+            jump: Some(100),
+            flat: Some(2),
+            ..Default::default()
+        });
+        detector.read("1\n1\n\n500\n".as_bytes())?;
+        let found = detector.finish();
+        assert_eq!(
+            found.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            vec!["1..3: jump from 1 to 500", "0..2: flat line at 1"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    fs::File,
+    io::{stdin, BufReader},
+};
+
+use anyhow::{bail, Context, Result};
+use aoc_common::with_file;
+use clap::{Parser, ValueEnum};
+use day01::{read_measurements, Detector, Outlier, Reading, Sweep, Thresholds};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Type {
+    I64,
+    I128,
+    U64,
+}
+
+/// Counts depth changes of a sonar log and flags suspicious readings,
+/// without loading it in memory.
+#[derive(Parser)]
+struct Args {
+    /// Sizes of the windows compared
+    #[clap(short, long = "window", default_values = &["1", "3"])]
+    windows: Vec<usize>,
+
+    /// Distances of the windows compared
+    #[clap(short = 'k', long = "offset", default_value = "1")]
+    offsets: Vec<usize>,
+
+    /// Flag consecutive readings differing by more than this
+    #[clap(long)]
+    jump: Option<u128>,
+
+    /// Flag this many identical readings in a row
+    #[clap(long)]
+    flat: Option<usize>,
+
+    /// Flag outliers against this many readings before them
+    #[clap(long)]
+    outlier_window: Option<usize>,
+
+    /// Z-score above which a reading is an outlier
+    #[clap(long, default_value = "3")]
+    z: f64,
+
+    /// Integer type of the readings, which window sums must fit in
+    #[clap(long = "type", value_enum, default_value = "i64")]
+    ty: Type,
+
+    /// Sonar log, `-` reads from stdin
+    #[clap(default_value = "-")]
+    input: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    if args.windows.contains(&0) {
+        bail!("Window size must be positive");
+    }
+    if args.offsets.contains(&0) {
+        bail!("Offset must be positive");
+    }
+    if args.flat.is_some_and(|n| n < 2) || args.outlier_window.is_some_and(|n| n < 2) {
+        bail!("Flat lines and outlier windows must be at least 2 readings");
+    }
+
+    match args.ty {
+        Type::I64 => analyze::<i64>(&args),
+        Type::I128 => analyze::<i128>(&args),
+        Type::U64 => analyze::<u64>(&args),
+    }
+}
+
+fn analyze<T: Reading>(args: &Args) -> Result<()> {
+    let mut sweep = Sweep::<T>::report(&args.windows, &args.offsets);
+    let mut detector = Detector::new(Thresholds {
+        jump: args.jump,
+        flat: args.flat,
+        outlier: args
+            .outlier_window
+            .map(|window| Outlier { window, z: args.z }),
+    });
+    let mut push = |m, line| {
+        detector.push(m);
+        for anomaly in detector.take() {
+            println!("Anomaly at {}", anomaly);
+        }
+        // The other windows are still counted, so the log is read on
+        if let Err(e) = sweep.push_at(m, line) {
+            eprintln!("Warning: {}", e);
+        }
+        Ok(())
+    };
+    if args.input == "-" {
+        read_measurements(stdin().lock(), &mut 0, &mut push)?;
+    } else {
+        let file =
+            File::open(&args.input).with_context(|| format!("Failed to open {:?}", args.input))?;
+        read_measurements(BufReader::new(file), &mut 0, &mut push)
+            .map_err(|e| with_file(e, &args.input))?;
+    }
+    for anomaly in detector.finish() {
+        println!("Anomaly at {}", anomaly);
+    }
+
+    println!("Measurements: {}", sweep.len());
+    println!(
+        "{:>6} {:>6}  {:<24} {:>9} {:>9} {:>9} {:>9}",
+        "Window", "Offset", "Compares", "Increases", "Decreases", "Unchanged", "Overflows"
+    );
+    for row in sweep.rows() {
+        println!(
+            "{:>6} {:>6}  {:<24} {:>9} {:>9} {:>9} {:>9}",
+            row.size,
+            row.offset,
+            row.comparison.to_string(),
+            row.changes.increases,
+            row.changes.decreases,
+            row.changes.unchanged,
+            row.overflows
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use aoc_common::{Answer, Solution};
+
+mod anomaly;
+mod reading;
+mod sweep;
+
+pub use anomaly::{anomalies, Anomaly, Detector, Outlier, Reason, Thresholds};
+pub use reading::{parse_reading, parse_readings, Reading, ReadingError, Readings};
+pub use sweep::{read_measurements, Changes, Comparison, Row, Sweep};
+
+use reading::checked_sum;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Readings<i64>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        parse_readings(input).map_err(|e| {
+            let line = input.lines().nth(e.line() - 1).unwrap_or_default();
+            match e.to_parse_error(line) {
+                Some(parse_error) => parse_error.into(),
+                None => e.into(),
+            }
+        })
+    }
+
+    fn part1(readings: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(depth(&readings.values).into())
+    }
+
+    fn part2(readings: &Self::Input) -> anyhow::Result<Answer> {
+        // Sums are numbered by measurement, which are not on every line
+        let increases = depth2(&readings.values, 3).map_err(|e| match e {
+            ReadingError::SumOverflow { line, ty } => ReadingError::SumOverflow {
+                line: readings.lines[line - 1],
+                ty,
+            },
+            e => e,
+        })?;
+        Ok(increases.into())
+    }
+}
+
+fn depth<T: Reading>(measurements: &[T]) -> usize {
+    measurements.windows(2).filter(|p| p[1] > p[0]).count()
+}
+
+/// Fails if a window sum overflows `T`, numbering the measurement it ends
+/// at from 1 as if they were one per line.
+fn depth2<T: Reading>(measurements: &[T], window_size: usize) -> Result<usize, ReadingError> {
+    let mut sums = measurements
+        .windows(window_size)
+        .enumerate()
+        .map(|(i, w)| checked_sum(w, i + window_size));
+
+    let mut increases = 0;
+    let mut previous = match sums.next() {
+        Some(sum) => sum?,
+        None => return Ok(0),
+    };
+    for sum in sums {
+        let sum = sum?;
+        if sum > previous {
+            increases += 1;
+        }
+        previous = sum;
+    }
+    Ok(increases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::ParseError;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_depth() {
+        assert_eq!(
+            depth(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]),
+            7
+        );
+        assert_eq!(
+            depth2(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263], 1),
+            Ok(7)
+        );
+        assert_eq!(
+            depth2(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263], 3),
+            Ok(5)
+        );
+    }
+
+    #[test]
+    fn test_wide_readings() {
+        assert_eq!(depth2(&[-5i64, -3, -4, -1], 2), Ok(2));
+        assert_eq!(depth2(&[i128::MAX - 1, 0, 1], 2), Ok(0));
+        assert_eq!(
+            depth2(&[1, 2, u64::MAX, 0], 2),
+            Err(ReadingError::SumOverflow { line: 3, ty: "u64" })
+        );
+
+        let readings = Day01::parse("1\n\n\n9223372036854775807\n5\n6").unwrap();
+        let e = Day01::part2(&readings).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Sum of the window ending on line 5 overflows i64"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let e = Day01::parse("199\n-200\n20x8\n")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "20x8"));
+
+        let e = Day01::parse("199\n\n 99999999999999999999\n")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!((e.line, e.column), (3, 2));
+        assert_eq!(e.message, "Measurement does not fit in i64");
+    }
+
+    /// Compares the sums of each window and the one following it.
+    fn depth2_naive(measurements: &[usize], window_size: usize) -> usize {
+        let sum = |i: usize| measurements[i..i + window_size].iter().sum::<usize>();
+        (0..measurements.len().saturating_sub(window_size))
+            .filter(|&i| sum(i + 1) > sum(i))
+            .count()
+    }
+
+    proptest! {
+        #[test]
+        fn test_depth2_matches_naive(
+            measurements in prop::collection::vec(0..10_000usize, 1..50),
+            window_size in 1..6usize,
+        ) {
+            prop_assert_eq!(
+                depth2(&measurements, window_size),
+                Ok(depth2_naive(&measurements, window_size))
+            );
+        }
+
+        #[test]
+        fn test_depth_is_unit_window(measurements in prop::collection::vec(0..10_000usize, 1..50)) {
+            prop_assert_eq!(Ok(depth(&measurements)), depth2(&measurements, 1));
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Integer types measurements can be read into.
+
+use std::{
+    fmt::{Debug, Display},
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
+
+use aoc_common::ParseError;
+use thiserror::Error;
+
+/// An integer type of measurements, with the checked arithmetic window
+/// sums are computed with.
+pub trait Reading: Copy + Ord + Default + Debug + Display + FromStr<Err = ParseIntError> {
+    /// Name of the type in errors
+    const TYPE: &'static str;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Distance of the two, which fits in `u128` for every integer type.
+    fn abs_diff(self, other: Self) -> u128;
+
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_reading {
+    ($($t:ty),*) => {
+        $(
+            impl Reading for $t {
+                const TYPE: &'static str = stringify!($t);
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn abs_diff(self, other: Self) -> u128 {
+                    <$t>::abs_diff(self, other) as u128
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_reading!(i32, i64, i128, u32, u64, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReadingError {
+    #[error("Invalid measurement {text:?} on line {line}")]
+    Invalid {
+        line: usize,
+        text: String,
+        source: ParseIntError,
+    },
+
+    #[error("Measurement {text} on line {line} does not fit in {ty}")]
+    OutOfRange {
+        line: usize,
+        text: String,
+        ty: &'static str,
+    },
+
+    #[error("Sum of the window ending on line {line} overflows {ty}")]
+    SumOverflow { line: usize, ty: &'static str },
+}
+
+impl ReadingError {
+    /// Line of the offending measurement, or of the last one of the
+    /// overflowing window.
+    pub fn line(&self) -> usize {
+        match self {
+            ReadingError::Invalid { line, .. }
+            | ReadingError::OutOfRange { line, .. }
+            | ReadingError::SumOverflow { line, .. } => *line,
+        }
+    }
+
+    /// The error pointing at the measurement within `source_line`, the line
+    /// it was read from, if it was a single one.
+    pub fn to_parse_error(&self, source_line: &str) -> Option<ParseError> {
+        let (line, text, message) = match self {
+            ReadingError::Invalid { line, text, source } => {
+                (line, text, format!("Invalid measurement: {}", source))
+            }
+            ReadingError::OutOfRange { line, text, ty } => {
+                (line, text, format!("Measurement does not fit in {}", ty))
+            }
+            ReadingError::SumOverflow { .. } => return None,
+        };
+        let offset = source_line.find(text.as_str()).unwrap_or(0);
+        Some(ParseError {
+            line: *line,
+            ..ParseError::at(source_line, offset, text.len(), message)
+        })
+    }
+}
+
+/// Parses the measurement on `line`, ignoring surrounding whitespace.
+pub fn parse_reading<T: Reading>(text: &str, line: usize) -> Result<T, ReadingError> {
+    let text = text.trim();
+    text.parse()
+        .map_err(|source: ParseIntError| match source.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ReadingError::OutOfRange {
+                line,
+                text: text.into(),
+                ty: T::TYPE,
+            },
+            _ => ReadingError::Invalid {
+                line,
+                text: text.into(),
+                source,
+            },
+        })
+}
+
+/// Measurements along with the lines they were read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Readings<T> {
+    pub values: Vec<T>,
+    /// Line of each value, counted from 1
+    pub lines: Vec<usize>,
+}
+
+/// Parses one measurement per line, skipping blank lines.
+pub fn parse_readings<T: Reading>(input: &str) -> Result<Readings<T>, ReadingError> {
+    let mut readings = Readings {
+        values: Vec::new(),
+        lines: Vec::new(),
+    };
+    for (i, text) in input.lines().enumerate() {
+        if !text.trim().is_empty() {
+            readings.values.push(parse_reading(text, i + 1)?);
+            readings.lines.push(i + 1);
+        }
+    }
+    Ok(readings)
+}
+
+/// Sum of the window, or an error naming the line of the measurement it
+/// ends at.
+pub(crate) fn checked_sum<'a, T: Reading + 'a>(
+    window: impl IntoIterator<Item = &'a T>,
+    line: usize,
+) -> Result<T, ReadingError> {
+    window
+        .into_iter()
+        .try_fold(T::default(), |sum, &m| sum.checked_add(m))
+        .ok_or(ReadingError::SumOverflow { line, ty: T::TYPE })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_readings() {
+        assert_eq!(
+            parse_readings::<i64>("-3\n\n 5\n"),
+            Ok(Readings {
+                values: vec![-3, 5],
+                lines: vec![1, 3]
+            })
+        );
+        assert_eq!(
+            parse_readings::<i128>("170141183460469231731687303715884105727").map(|r| r.values),
+            Ok(vec![i128::MAX])
+        );
+
+        let e = parse_readings::<u64>("1\n-3\n").unwrap_err();
+        assert!(matches!(e, ReadingError::Invalid { line: 2, .. }));
+        assert_eq!(e.to_string(), r#"Invalid measurement "-3" on line 2"#);
+
+        let e = parse_readings::<i64>("1\n2\n\n99999999999999999999\n").unwrap_err();
+        assert_eq!(
+            e,
+            ReadingError::OutOfRange {
+                line: 4,
+                text: "99999999999999999999".into(),
+                ty: "i64"
+            }
+        );
+        assert_eq!(e.line(), 4);
+    }
+
+    #[test]
+    fn test_checked_sum() {
+        assert_eq!(checked_sum(&[-1i64, 2, 3], 2), Ok(4));
+        assert_eq!(
+            checked_sum(&[u64::MAX, 1], 5),
+            Err(ReadingError::SumOverflow { line: 5, ty: "u64" })
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Incremental sonar sweep, for logs too long to be read in one go.
+
+use std::{cmp::Ordering, collections::VecDeque, fmt::Display, io::BufRead, ops::RangeBounds};
+
+use anyhow::Result;
+
+use crate::reading::{checked_sum, parse_reading, Reading, ReadingError};
+
+/// Which measurements decide how a window sum compares to the one
+/// `offset` later.
+///
+/// The two windows share all but their ends, so the comparison is the one
+/// of the `len` measurements `lead` after the first window starts with the
+/// first `len` measurements of it: `m[i + lead..i + lead + len]` against
+/// `m[i..i + len]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub len: usize,
+    pub lead: usize,
+}
+
+impl Comparison {
+    pub fn new(size: usize, offset: usize) -> Self {
+        Self {
+            len: size.min(offset),
+            lead: size.max(offset),
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.len == 1 {
+            write!(f, "m[i+{}] vs m[i]", self.lead)
+        } else {
+            write!(
+                f,
+                "m[i+{}..i+{}] vs m[i..i+{}]",
+                self.lead,
+                self.lead + self.len,
+                self.len
+            )
+        }
+    }
+}
+
+/// How window sums changed against the ones before them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub increases: usize,
+    pub decreases: usize,
+    pub unchanged: usize,
+}
+
+impl Changes {
+    fn count<T: Ord>(&mut self, before: T, after: T) {
+        match after.cmp(&before) {
+            Ordering::Greater => self.increases += 1,
+            Ordering::Less => self.decreases += 1,
+            Ordering::Equal => self.unchanged += 1,
+        }
+    }
+}
+
+/// Changes counted so far for one window size and offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    pub size: usize,
+    pub offset: usize,
+    pub comparison: Comparison,
+    pub changes: Changes,
+    /// Comparisons left out of `changes` as their sums overflowed
+    pub overflows: usize,
+}
+
+/// The last measurements, up to a fixed number of them.
+#[derive(Debug, Clone)]
+pub(crate) struct Recent<T> {
+    size: usize,
+    values: VecDeque<T>,
+}
+
+impl<T: Copy> Recent<T> {
+    pub(crate) fn new(size: usize) -> Self {
+        Self {
+            size,
+            values: VecDeque::with_capacity(size + 1),
+        }
+    }
+
+    /// Adds the measurement, returning the one it pushes out of a full
+    /// window.
+    pub(crate) fn push(&mut self, measurement: T) -> Option<T> {
+        self.values.push_back(measurement);
+        if self.values.len() > self.size {
+            self.values.pop_front()
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.values.len() == self.size
+    }
+
+    pub(crate) fn get(&self, index: usize) -> T {
+        self.values[index]
+    }
+
+    pub(crate) fn range(&self, range: impl RangeBounds<usize>) -> impl Iterator<Item = &T> {
+        self.values.range(range)
+    }
+}
+
+/// Running comparison of the window sums of one size with the ones
+/// `offset` later.
+///
+/// Only the measurements deciding the next comparison are kept, so only
+/// the sums of its ends have to fit in `T`. They are kept as running sums,
+/// summed again when they could not be slid.
+#[derive(Debug, Clone)]
+struct Window<T> {
+    size: usize,
+    offset: usize,
+    comparison: Comparison,
+    recent: Recent<T>,
+    /// Sums of the two ends compared last
+    ends: Option<(T, T)>,
+    changes: Changes,
+    overflows: usize,
+}
+
+impl<T: Reading> Window<T> {
+    fn new(size: usize, offset: usize) -> Self {
+        assert!(size > 0, "Window size must be positive");
+        assert!(offset > 0, "Offset must be positive");
+        let comparison = Comparison::new(size, offset);
+        Self {
+            size,
+            offset,
+            comparison,
+            recent: Recent::new(comparison.lead + comparison.len),
+            ends: None,
+            changes: Changes::default(),
+            overflows: 0,
+        }
+    }
+
+    fn push(&mut self, measurement: T, line: usize) -> Result<(), ReadingError> {
+        let Comparison { len, lead } = self.comparison;
+        let leaving = self.recent.push(measurement);
+        if !self.recent.is_full() {
+            return Ok(());
+        }
+
+        // Each end moves by one measurement: the first one leaves the tail
+        // and the one before the head enters it
+        let slid = self.ends.zip(leaving).and_then(|((tail, head), first)| {
+            let tail = tail
+                .checked_sub(first)?
+                .checked_add(self.recent.get(len - 1))?;
+            let head = head
+                .checked_sub(self.recent.get(lead - 1))?
+                .checked_add(measurement)?;
+            Some((tail, head))
+        });
+        self.ends = None;
+        let (tail, head) = match slid {
+            Some(ends) => ends,
+            None => {
+                let tail = checked_sum(self.recent.range(..len), line);
+                let head = checked_sum(self.recent.range(lead..), line);
+                match tail.and_then(|tail| Ok((tail, head?))) {
+                    Ok(ends) => ends,
+                    Err(e) => {
+                        self.overflows += 1;
+                        return Err(e);
+                    }
+                }
+            }
+        };
+        self.ends = Some((tail, head));
+        self.changes.count(tail, head);
+        Ok(())
+    }
+
+    fn row(&self) -> Row {
+        Row {
+            size: self.size,
+            offset: self.offset,
+            comparison: self.comparison,
+            changes: self.changes,
+            overflows: self.overflows,
+        }
+    }
+}
+
+/// Counts depth changes for a set of window sizes and offsets, taking
+/// measurements one at a time.
+#[derive(Debug, Clone)]
+pub struct Sweep<T> {
+    windows: Vec<Window<T>>,
+    lines: usize,
+    measurements: usize,
+}
+
+impl<T: Reading> Sweep<T> {
+    /// Compares each window with the next one. Panics if any of the window
+    /// sizes is zero.
+    pub fn new(window_sizes: &[usize]) -> Self {
+        Self::report(window_sizes, &[1])
+    }
+
+    /// Compares each window with the ones `offset` later, for every pair of
+    /// window size and offset. Panics if any of them is zero.
+    pub fn report(window_sizes: &[usize], offsets: &[usize]) -> Self {
+        Self {
+            windows: window_sizes
+                .iter()
+                .flat_map(|&size| offsets.iter().map(move |&offset| Window::new(size, offset)))
+                .collect(),
+            lines: 0,
+            measurements: 0,
+        }
+    }
+
+    /// Fails if the sums compared overflow `T`, still counting the windows
+    /// that did not. The measurement takes the line after the last one.
+    pub fn push(&mut self, measurement: T) -> Result<(), ReadingError> {
+        self.lines += 1;
+        self.push_at(measurement, self.lines)
+    }
+
+    /// Like `push`, for a measurement read from `line`.
+    pub fn push_at(&mut self, measurement: T, line: usize) -> Result<(), ReadingError> {
+        self.measurements += 1;
+        self.windows
+            .iter_mut()
+            .map(|window| window.push(measurement, line))
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Feeds the measurements read from `reader`, one per line, skipping
+    /// blank lines. Line numbers in errors carry on from previous reads.
+    pub fn read(&mut self, reader: impl BufRead) -> Result<()> {
+        let mut lines = self.lines;
+        let result = read_measurements(reader, &mut lines, |m, line| self.push_at(m, line));
+        self.lines = lines;
+        result
+    }
+
+    /// Number of measurements seen so far.
+    pub fn len(&self) -> usize {
+        self.measurements
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.measurements == 0
+    }
+
+    /// Increases counted so far between consecutive windows of
+    /// `window_size`, if it is tracked.
+    pub fn increases(&self, window_size: usize) -> Option<usize> {
+        self.windows
+            .iter()
+            .find(|w| w.size == window_size && w.offset == 1)
+            .map(|w| w.changes.increases)
+    }
+
+    /// Changes counted so far, by window size then offset in the order
+    /// they were given.
+    pub fn rows(&self) -> impl Iterator<Item = Row> + '_ {
+        self.windows.iter().map(Window::row)
+    }
+}
+
+/// Calls `push` with each measurement read from `reader`, one per line,
+/// and its line, skipping blank lines. `lines` counts the lines read,
+/// numbering the ones in errors.
+///
+/// Measurements which cannot be read are reported as a `ParseError` showing
+/// their line.
+pub fn read_measurements<T: Reading>(
+    mut reader: impl BufRead,
+    lines: &mut usize,
+    mut push: impl FnMut(T, usize) -> Result<(), ReadingError>,
+) -> Result<()> {
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if reader.read_line(&mut buf)? == 0 {
+            return Ok(());
+        }
+        *lines += 1;
+
+        let line = buf.trim_end_matches(&['\n', '\r'][..]);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let measurement = match parse_reading(line, *lines) {
+            Ok(m) => m,
+            Err(e) => return Err(e.to_parse_error(line).map_or_else(|| e.into(), Into::into)),
+        };
+        push(measurement, *lines)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::depth2;
+    use aoc_common::ParseError;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_sweep() -> Result<()> {
+        let mut sweep = Sweep::<usize>::new(&[1, 3]);
+        sweep.read("199\n200\n208\n210\n200\n\n207\n240\n".as_bytes())?;
+        assert_eq!(sweep.increases(1), Some(5));
+        sweep.read("269\r\n260\r\n263".as_bytes())?;
+
+        assert_eq!(sweep.len(), 10);
+        assert_eq!(sweep.increases(1), Some(7));
+        assert_eq!(sweep.increases(3), Some(5));
+        assert_eq!(sweep.increases(2), None);
+        assert_eq!(
+            sweep
+                .rows()
+                .map(|r| (r.size, r.changes.increases))
+                .collect::<Vec<_>>(),
+            vec![(1, 7), (3, 5)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sweep_error() -> Result<()> {
+        let mut sweep = Sweep::<u64>::new(&[1]);
+        sweep.read("199\n200\n".as_bytes())?;
+        let e = sweep
+            .read("\n  2x8\n".as_bytes())
+            .unwrap_err()
+            .downcast::<ParseError>()?;
+        assert_eq!((e.line, e.column), (4, 3));
+        assert_eq!(e.source_line, "  2x8");
+
+        let mut sweep = Sweep::<i64>::report(&[2], &[1, 2]);
+        for m in [1, 1, 1] {
+            sweep.push(m)?;
+        }
+        assert!(matches!(
+            sweep.push(i64::MAX),
+            Err(ReadingError::SumOverflow { line: 4, .. })
+        ));
+        sweep.push(0)?;
+        assert_eq!(
+            sweep
+                .rows()
+                .map(|r| (r.changes.increases, r.overflows))
+                .collect::<Vec<_>>(),
+            vec![(1, 0), (1, 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_report() {
+        let mut sweep = Sweep::report(&[3], &[1, 2, 4]);
+        for m in [199, 200, 208, 210, 200, 207, 240, 269, 260, 263u32] {
+            sweep.push(m).unwrap();
+        }
+        let rows = sweep.rows().collect::<Vec<_>>();
+
+        assert_eq!(rows[0].comparison.to_string(), "m[i+3] vs m[i]");
+        assert_eq!(
+            rows[0].changes,
+            Changes {
+                increases: 5,
+                decreases: 1,
+                unchanged: 1
+            }
+        );
+        assert_eq!(rows[1].comparison.to_string(), "m[i+3..i+5] vs m[i..i+2]");
+        assert_eq!(rows[2].comparison.to_string(), "m[i+4..i+7] vs m[i..i+3]");
+        assert_eq!(
+            rows.iter().map(|r| r.offset).collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+    }
+
+    /// Compares the sums of each window and the one `offset` later.
+    fn changes_naive(measurements: &[usize], size: usize, offset: usize) -> Changes {
+        let sum = |i: usize| measurements[i..i + size].iter().sum::<usize>();
+        let mut changes = Changes::default();
+        for i in 0..measurements.len().saturating_sub(size + offset - 1) {
+            changes.count(sum(i), sum(i + offset));
+        }
+        changes
+    }
+
+    proptest! {
+        #[test]
+        fn test_report_matches_naive(
+            measurements in prop::collection::vec(0..20usize, 0..50),
+            window_sizes in prop::collection::vec(1..6usize, 1..4),
+            offsets in prop::collection::vec(1..6usize, 1..4),
+        ) {
+            let mut sweep = Sweep::report(&window_sizes, &offsets);
+            for &m in measurements.iter() {
+                sweep.push(m).unwrap();
+            }
+            for row in sweep.rows() {
+                prop_assert_eq!(
+                    row.changes,
+                    changes_naive(&measurements, row.size, row.offset)
+                );
+            }
+        }
+
+        #[test]
+        fn test_sweep_matches_depth2(
+            measurements in prop::collection::vec(0..10_000usize, 1..50),
+            window_sizes in prop::collection::vec(1..6usize, 1..4),
+        ) {
+            let mut sweep = Sweep::new(&window_sizes);
+            for &m in measurements.iter() {
+                sweep.push(m).unwrap();
+            }
+            for row in sweep.rows() {
+                prop_assert_eq!(
+                    row.changes.increases,
+                    depth2(&measurements, row.size).unwrap()
+                );
+            }
+        }
+    }
+}
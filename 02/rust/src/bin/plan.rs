@@ -0,0 +1,45 @@
+use std::num::NonZeroU64;
+
+use anyhow::Result;
+use clap::Parser;
+use day02::{plan, write_course, Limits, Model};
+
+/// Prints the shortest course reaching a position.
+#[derive(Parser)]
+struct Args {
+    /// How commands steer the submarine
+    #[clap(long, value_enum, default_value = "angled")]
+    model: Model,
+
+    /// Largest amount of a single forward or back command
+    #[clap(long)]
+    max_forward: Option<NonZeroU64>,
+
+    /// Largest amount of a single down or up command
+    #[clap(long)]
+    max_dive: Option<NonZeroU64>,
+
+    /// Largest aim a single set aim command may set
+    #[clap(long)]
+    max_aim: Option<NonZeroU64>,
+
+    #[clap(allow_hyphen_values = true)]
+    distance: i64,
+
+    #[clap(allow_hyphen_values = true)]
+    depth: i64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let limits = Limits {
+        forward: args.max_forward,
+        dive: args.max_dive,
+        aim: args.max_aim,
+    };
+    print!(
+        "{}",
+        write_course(&plan(args.model, args.distance, args.depth, limits)?)
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::io::stdout;
+
+use anyhow::Result;
+use aoc_common::{read_input, with_file};
+use clap::Parser;
+use day02::{parse_course, write_csv, Recorder, Registry, SubmarineController};
+
+/// Prints the trajectory of a course as CSV.
+#[derive(Parser)]
+struct Args {
+    /// Controller steering the submarine, `direct` or `angled`
+    #[clap(long, default_value = "angled")]
+    model: String,
+
+    /// Course to follow, `-` reads from stdin
+    #[clap(default_value = "-")]
+    input: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut recorder = Recorder::new(Registry::default().create(&args.model)?);
+    let course = read_input(&args.input)?;
+    parse_course(&course)
+        .map_err(anyhow::Error::from)
+        .and_then(|statements| Ok(recorder.follow(&statements)?))
+        .map_err(|e| with_file(e, &args.input))?;
+    write_csv(stdout().lock(), recorder.trajectory())?;
+    Ok(())
+}
@@ -0,0 +1,669 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    io::{self, Write},
+};
+
+use aoc_common::{Answer, ParseError, Solution};
+use thiserror::Error;
+
+mod plan;
+mod registry;
+mod validate;
+
+pub use plan::{plan, write_course, Limits, Model, Unreachable};
+pub use registry::{Factory, Registry};
+pub use validate::{validate, Envelope, Validator, Violation};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Statement>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_course(input)?)
+    }
+
+    fn part1(course: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(Registry::default().course("direct", course)?.into())
+    }
+
+    fn part2(course: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(Registry::default().course("angled", course)?.into())
+    }
+}
+
+/// A single command of a course.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Down(i64),
+    Up(i64),
+    Forward(i64),
+    Back(i64),
+    SetAim(i64),
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Down(a) => write!(f, "down {}", a),
+            Command::Up(a) => write!(f, "up {}", a),
+            Command::Forward(a) => write!(f, "forward {}", a),
+            Command::Back(a) => write!(f, "back {}", a),
+            Command::SetAim(a) => write!(f, "set aim {}", a),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Command {
+        command: Command,
+        line: usize,
+    },
+    /// A block executed the given number of times
+    Repeat(usize, Vec<Statement>),
+}
+
+/// Most commands a course may take, counting repeated ones each time they
+/// run, and each round of an empty repeat as one.
+pub const MAX_COMMANDS: u64 = 1_000_000;
+
+/// Commands the statements take, as counted against `MAX_COMMANDS`.
+fn commands(statements: &[Statement]) -> u64 {
+    statements
+        .iter()
+        .map(|s| match s {
+            Statement::Command { .. } => 1,
+            Statement::Repeat(times, body) => (*times as u64).saturating_mul(commands(body).max(1)),
+        })
+        .fold(0, u64::saturating_add)
+}
+
+/// Fails the parse with `message` underlining `text`, a slice of `source`,
+/// unless it already failed. Returns what was `expected` for the parser.
+fn fail(
+    failure: &RefCell<Option<ParseError>>,
+    source: &str,
+    text: &str,
+    message: String,
+    expected: &'static str,
+) -> &'static str {
+    failure
+        .borrow_mut()
+        .get_or_insert_with(|| ParseError::at_slice(source, text, message));
+    expected
+}
+
+/// The statements, failing at the first one taking the block past
+/// `MAX_COMMANDS`.
+fn bounded(
+    failure: &RefCell<Option<ParseError>>,
+    source: &str,
+    located: Vec<(usize, Statement)>,
+) -> Result<Vec<Statement>, &'static str> {
+    let mut total = 0u64;
+    let mut statements = Vec::with_capacity(located.len());
+    for (offset, statement) in located {
+        total = total.saturating_add(commands(std::slice::from_ref(&statement)));
+        if total > MAX_COMMANDS {
+            let line = source[offset..].split('\n').next().unwrap_or_default();
+            let message = format!("Course takes more than {} commands", MAX_COMMANDS);
+            return Err(fail(
+                failure,
+                source,
+                line.trim_end(),
+                message,
+                "a shorter course",
+            ));
+        }
+        statements.push(statement);
+    }
+    Ok(statements)
+}
+
+peg::parser! {
+    grammar parser(
+        source: &str,
+        line_starts: &[usize],
+        failure: &RefCell<Option<ParseError>>,
+    ) for str {
+        pub(crate) rule course() -> Vec<Statement>
+            = s:statements() ![_] { s }
+        rule statements() -> Vec<Statement>
+            = blank() _ s:(located() ** (eol() blank() _)) (eol() blank())? _ comment()? {?
+                bounded(failure, source, s)
+            }
+        rule located() -> (usize, Statement)
+            = p:position!() s:statement() { (p, s) }
+        rule statement() -> Statement
+            = p:position!() c:command() {
+                Statement::Command { command: c, line: line_starts.partition_point(|&s| s <= p) }
+            }
+            / repeat()
+        rule repeat() -> Statement
+            = "repeat " n:count() _ "{" b:statements() "}" { Statement::Repeat(n, b) }
+        rule command() -> Command
+            = "down " a:amount() { Command::Down(a) }
+            / "up " a:amount() { Command::Up(a) }
+            / "forward " a:amount() { Command::Forward(a) }
+            / "back " a:amount() { Command::Back(a) }
+            / "set aim " a:amount() { Command::SetAim(a) }
+        rule amount() -> i64
+            = n:$(['-' | '+']? ['0'..='9']+) {?
+                n.parse().map_err(|_| {
+                    let message = format!("Amount {} does not fit in 64 bits", n);
+                    fail(failure, source, n, message, "amount within 64 bits")
+                })
+            }
+        rule count() -> usize
+            = n:$(['0'..='9']+) {?
+                n.parse().map_err(|_| {
+                    let message = format!("Repeat count {} is too large", n);
+                    fail(failure, source, n, message, "repeat count")
+                })
+            }
+        rule _ = [' ' | '\t']*
+        rule comment() = "#" [^'\n']*
+        rule eol() = _ comment()? "\n"
+        rule blank() = eol()*
+    }
+}
+
+/// Parses a course of commands, one per line, where `#` starts a comment.
+/// `repeat N { ... }` blocks can span lines, as long as the course takes
+/// at most `MAX_COMMANDS` commands.
+pub fn parse_course(course: &str) -> Result<Vec<Statement>, ParseError> {
+    // Byte offsets lines start at, to number the commands by
+    let line_starts = std::iter::once(0)
+        .chain(course.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+    let failure = RefCell::new(None);
+    parser::course(course, course, &line_starts, &failure).map_err(|e| {
+        failure
+            .take()
+            .unwrap_or_else(|| ParseError::from_peg(course, course, e))
+    })
+}
+
+/// Where the submarine is, and where it is heading to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub distance: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// A controller state out of the range of its integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Arithmetic overflow")]
+pub struct Overflow;
+
+/// `checked_*` results of controller arithmetic, as a `Result`.
+pub fn checked(result: Option<i64>) -> Result<i64, Overflow> {
+    result.ok_or(Overflow)
+}
+
+/// A command of a course the controller could not execute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("`{command}` on line {line} overflows")]
+pub struct CommandOverflow {
+    pub command: Command,
+    pub line: usize,
+}
+
+pub trait SubmarineController {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow>;
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow>;
+    fn course(&self) -> Result<i64, Overflow>;
+    fn position(&self) -> Position;
+
+    /// Moves backwards, undoing a forward move by default.
+    fn back(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.forward(checked(amount.checked_neg())?)
+    }
+
+    /// Points the submarine at `aim`. Controllers not steering by aim
+    /// ignore it by default.
+    fn set_aim(&mut self, _aim: i64) -> Result<(), Overflow> {
+        Ok(())
+    }
+
+    /// Executes the command found on `line` of the course.
+    fn execute_at(&mut self, command: Command, _line: usize) -> Result<(), Overflow> {
+        self.execute(command)
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), Overflow> {
+        match command {
+            Command::Down(a) => self.dive(a),
+            Command::Up(a) => self.dive(checked(a.checked_neg())?),
+            Command::Forward(a) => self.forward(a),
+            Command::Back(a) => self.back(a),
+            Command::SetAim(a) => self.set_aim(a),
+        }
+    }
+
+    /// Executes the statements in order, repeated blocks as many times as
+    /// asked, stopping at the first command overflowing.
+    fn follow(&mut self, statements: &[Statement]) -> Result<(), CommandOverflow> {
+        for statement in statements {
+            match statement {
+                &Statement::Command { command, line } => self
+                    .execute_at(command, line)
+                    .map_err(|_| CommandOverflow { command, line })?,
+                Statement::Repeat(_, body) if body.is_empty() => {}
+                Statement::Repeat(times, body) => {
+                    for _ in 0..*times {
+                        self.follow(body)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Steers a new controller through the course.
+    fn run(course: &str) -> anyhow::Result<Self>
+    where
+        Self: Sized + Default,
+    {
+        let mut ctrl: Self = Default::default();
+        ctrl.follow(&parse_course(course)?)?;
+        Ok(ctrl)
+    }
+
+    fn parse(course: &str) -> anyhow::Result<i64>
+    where
+        Self: Sized + Default,
+    {
+        Ok(Self::run(course)?.course()?)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DirectDive {
+    depth: i64,
+    distance: i64,
+}
+
+impl SubmarineController for DirectDive {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.depth = checked(self.depth.checked_add(amount))?;
+        Ok(())
+    }
+
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.distance = checked(self.distance.checked_add(amount))?;
+        Ok(())
+    }
+
+    fn course(&self) -> Result<i64, Overflow> {
+        checked(self.depth.checked_mul(self.distance))
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            distance: self.distance,
+            depth: self.depth,
+            aim: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AngledDive {
+    depth: i64,
+    distance: i64,
+    angle: i64,
+}
+
+impl SubmarineController for AngledDive {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.angle = checked(self.angle.checked_add(amount))?;
+        Ok(())
+    }
+
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+        // Nothing moves unless both fit
+        let distance = checked(self.distance.checked_add(amount))?;
+        let depth = checked(
+            self.angle
+                .checked_mul(amount)
+                .and_then(|d| self.depth.checked_add(d)),
+        )?;
+        self.distance = distance;
+        self.depth = depth;
+        Ok(())
+    }
+
+    fn set_aim(&mut self, aim: i64) -> Result<(), Overflow> {
+        self.angle = aim;
+        Ok(())
+    }
+
+    fn course(&self) -> Result<i64, Overflow> {
+        checked(self.depth.checked_mul(self.distance))
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            distance: self.distance,
+            depth: self.depth,
+            aim: self.angle,
+        }
+    }
+}
+
+/// Boxed controllers, like the ones of a `Registry`, steer like the one
+/// they hold.
+impl<C: SubmarineController + ?Sized> SubmarineController for Box<C> {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+        (**self).dive(amount)
+    }
+
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+        (**self).forward(amount)
+    }
+
+    fn course(&self) -> Result<i64, Overflow> {
+        (**self).course()
+    }
+
+    fn position(&self) -> Position {
+        (**self).position()
+    }
+
+    fn back(&mut self, amount: i64) -> Result<(), Overflow> {
+        (**self).back(amount)
+    }
+
+    fn set_aim(&mut self, aim: i64) -> Result<(), Overflow> {
+        (**self).set_aim(aim)
+    }
+
+    fn execute_at(&mut self, command: Command, line: usize) -> Result<(), Overflow> {
+        (**self).execute_at(command, line)
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), Overflow> {
+        (**self).execute(command)
+    }
+}
+
+/// Position after the `step`th command, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    pub step: usize,
+    pub command: Command,
+    pub position: Position,
+}
+
+/// Records the trajectory of the controller it wraps.
+#[derive(Debug, Default)]
+pub struct Recorder<C> {
+    controller: C,
+    trajectory: Vec<Snapshot>,
+}
+
+impl<C> Recorder<C> {
+    pub fn new(controller: C) -> Self {
+        Self {
+            controller,
+            trajectory: Vec::new(),
+        }
+    }
+
+    pub fn trajectory(&self) -> &[Snapshot] {
+        &self.trajectory
+    }
+
+    pub fn into_inner(self) -> C {
+        self.controller
+    }
+}
+
+impl<C: SubmarineController> Recorder<C> {
+    fn record(&mut self, command: Command) {
+        self.trajectory.push(Snapshot {
+            step: self.trajectory.len() + 1,
+            command,
+            position: self.controller.position(),
+        });
+    }
+}
+
+impl<C: SubmarineController> SubmarineController for Recorder<C> {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.dive(amount)
+    }
+
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.forward(amount)
+    }
+
+    fn course(&self) -> Result<i64, Overflow> {
+        self.controller.course()
+    }
+
+    fn position(&self) -> Position {
+        self.controller.position()
+    }
+
+    fn back(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.back(amount)
+    }
+
+    fn set_aim(&mut self, aim: i64) -> Result<(), Overflow> {
+        self.controller.set_aim(aim)
+    }
+
+    fn execute_at(&mut self, command: Command, line: usize) -> Result<(), Overflow> {
+        self.controller.execute_at(command, line)?;
+        self.record(command);
+        Ok(())
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), Overflow> {
+        self.controller.execute(command)?;
+        self.record(command);
+        Ok(())
+    }
+}
+
+/// Writes the trajectory as CSV, with a `step,command,distance,depth,aim`
+/// header.
+pub fn write_csv(mut w: impl Write, trajectory: &[Snapshot]) -> io::Result<()> {
+    writeln!(w, "step,command,distance,depth,aim")?;
+    for s in trajectory {
+        writeln!(
+            w,
+            "{},{},{},{},{}",
+            s.step, s.command, s.position.distance, s.position.depth, s.position.aim
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dive(course: &str) -> anyhow::Result<i64> {
+        DirectDive::parse(course)
+    }
+
+    fn dive_angled(course: &str) -> anyhow::Result<i64> {
+        AngledDive::parse(course)
+    }
+
+    #[test]
+    fn test_dive() {
+        assert_eq!(
+            dive(
+                "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+"
+            )
+            .unwrap(),
+            150
+        );
+    }
+
+    #[test]
+    fn test_angled_dive() {
+        assert_eq!(
+            dive_angled(
+                "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+"
+            )
+            .unwrap(),
+            900
+        );
+    }
+
+    #[test]
+    fn test_extended_course() -> anyhow::Result<()> {
+        let course = "# Survey run
+forward 5  # leave the dock
+repeat 2 {
+    down 3
+    repeat 2 { forward 2 }
+
+}
+back 4
+set aim -1
+forward +6
+up -2
+";
+        let direct = DirectDive::run(course)?.position();
+        assert_eq!((direct.distance, direct.depth), (15, 8));
+        let angled = AngledDive::run(course)?.position();
+        assert_eq!(
+            angled,
+            Position {
+                distance: 15,
+                depth: 6,
+                aim: 1
+            }
+        );
+
+        assert_eq!(
+            parse_course("repeat 3 { back 1 }")?,
+            vec![Statement::Repeat(
+                3,
+                vec![Statement::Command {
+                    command: Command::Back(1),
+                    line: 1
+                }]
+            )]
+        );
+        let e = parse_course("forward 1\nrepeat 2 {\n  down 1\n").unwrap_err();
+        assert_eq!(e.line, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_course_limit() -> anyhow::Result<()> {
+        let e = parse_course("forward 1\nrepeat 99999999999 {\n}\n").unwrap_err();
+        assert_eq!(e.message, "Course takes more than 1000000 commands");
+        assert_eq!(
+            (e.line, e.column, e.text.as_str()),
+            (2, 1, "repeat 99999999999 {")
+        );
+
+        let e = parse_course("repeat 1000 {\n  repeat 1001 { up 1 }\n}").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 1));
+        let e = parse_course("repeat 1000000 { up 1 }\nforward 1").unwrap_err();
+        assert_eq!(e.line, 2);
+
+        let statements = parse_course("repeat 1000 { repeat 1000 { up 1 } }")?;
+        assert_eq!(commands(&statements), MAX_COMMANDS);
+        assert_eq!(
+            DirectDive::parse("repeat 1000 { repeat 1000 { up 1 } }\nforward 1")
+                .unwrap_err()
+                .to_string()
+                .lines()
+                .next(),
+            Some("Course takes more than 1000000 commands")
+        );
+
+        let mut controller = DirectDive::default();
+        controller.follow(&[Statement::Repeat(usize::MAX, vec![])])?;
+        assert_eq!(controller.position(), Position::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() {
+        let e = AngledDive::run("down 5000000000\nforward 2000000000\n")
+            .unwrap_err()
+            .downcast::<CommandOverflow>()
+            .unwrap();
+        assert_eq!(
+            e,
+            CommandOverflow {
+                command: Command::Forward(2_000_000_000),
+                line: 2
+            }
+        );
+        assert_eq!(e.to_string(), "`forward 2000000000` on line 2 overflows");
+
+        let e = dive("down 9223372036854775807\nforward 2\n").unwrap_err();
+        assert_eq!(e.downcast::<Overflow>().unwrap(), Overflow);
+
+        let e = dive("forward 9223372036854775808\n")
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap();
+        assert_eq!((e.line, e.column), (1, 9));
+        assert_eq!(e.text, "9223372036854775808");
+        assert_eq!(
+            e.message,
+            "Amount 9223372036854775808 does not fit in 64 bits"
+        );
+    }
+
+    #[test]
+    fn test_trajectory() -> anyhow::Result<()> {
+        let course = "forward 5\ndown 5\nforward 8\nup 3\n";
+        let recorder = Recorder::<AngledDive>::run(course)?;
+        assert_eq!(
+            recorder.trajectory()[2],
+            Snapshot {
+                step: 3,
+                command: Command::Forward(8),
+                position: Position {
+                    distance: 13,
+                    depth: 40,
+                    aim: 5
+                }
+            }
+        );
+        assert_eq!(recorder.course(), Ok(520));
+
+        let mut csv = Vec::new();
+        write_csv(&mut csv, Recorder::<DirectDive>::run(course)?.trajectory())?;
+        assert_eq!(
+            String::from_utf8(csv)?,
+            "step,command,distance,depth,aim
+1,forward 5,5,0,0
+2,down 5,5,5,0
+3,forward 8,13,5,0
+4,up 3,13,2,0
+"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+//! Shortest courses reaching a position.
+
+use std::num::NonZeroU64;
+
+use clap::ValueEnum;
+use thiserror::Error;
+
+use crate::Command;
+
+/// How commands steer the submarine, as `DirectDive` or `AngledDive` do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Model {
+    Direct,
+    Angled,
+}
+
+/// Largest amounts of single commands, unlimited when `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Of `forward` and `back`
+    pub forward: Option<NonZeroU64>,
+    /// Of `down` and `up`
+    pub dive: Option<NonZeroU64>,
+    /// Of the aim set by `set aim`, which the dive limit does not bound as
+    /// it turns the submarine in one go
+    pub aim: Option<NonZeroU64>,
+}
+
+/// Whether `amount` is within `limit`.
+fn within(amount: i64, limit: Option<NonZeroU64>) -> bool {
+    limit.is_none_or(|limit| amount.unsigned_abs() <= limit.get())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The angled model cannot reach depth {depth} at distance {distance} with a single aim")]
+pub struct Unreachable {
+    pub distance: i64,
+    pub depth: i64,
+}
+
+/// Commands moving `total` in steps of at most `limit`, the last one taking
+/// the remainder.
+fn steps(
+    total: i64,
+    limit: Option<NonZeroU64>,
+    positive: fn(i64) -> Command,
+    negative: fn(i64) -> Command,
+) -> impl Iterator<Item = Command> {
+    let command = if total < 0 { negative } else { positive };
+    let mut left = total.unsigned_abs();
+    // Moving by `i64::MIN` takes two steps, as amounts are positive
+    let limit = limit.map_or(u64::MAX, NonZeroU64::get).min(i64::MAX as u64);
+    std::iter::from_fn(move || {
+        if left == 0 {
+            return None;
+        }
+        let step = left.min(limit);
+        left -= step;
+        Some(command(step as i64))
+    })
+}
+
+/// The shortest course taking the submarine from where it starts to
+/// `distance` and `depth`.
+///
+/// Commands change a single coordinate, so the course is as short as each
+/// of them can be moved in steps within `limits`. The angled model turns
+/// the submarine before moving it, so all of its forward moves share the
+/// same aim, which `depth` must be a multiple of `distance` for. It is
+/// turned by a single `set aim` where that takes fewer commands than diving
+/// within the dive limit, and the aim is within the aim limit.
+pub fn plan(
+    model: Model,
+    distance: i64,
+    depth: i64,
+    limits: Limits,
+) -> Result<Vec<Command>, Unreachable> {
+    let dive = match model {
+        Model::Direct => depth,
+        Model::Angled if distance == 0 && depth == 0 => 0,
+        Model::Angled => match depth.checked_rem(distance) {
+            Some(0) => depth / distance,
+            _ => return Err(Unreachable { distance, depth }),
+        },
+    };
+    let mut turn = steps(dive, limits.dive, Command::Down, Command::Up).collect::<Vec<_>>();
+    if model == Model::Angled && turn.len() > 1 && within(dive, limits.aim) {
+        turn = vec![Command::SetAim(dive)];
+    }
+    Ok(turn
+        .into_iter()
+        .chain(steps(
+            distance,
+            limits.forward,
+            Command::Forward,
+            Command::Back,
+        ))
+        .collect())
+}
+
+/// The course as text, one command per line.
+pub fn write_course(commands: &[Command]) -> String {
+    commands.iter().map(|c| format!("{}\n", c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{AngledDive, DirectDive, Position, SubmarineController};
+
+    fn limits(forward: u64, dive: u64) -> Limits {
+        Limits {
+            forward: NonZeroU64::new(forward),
+            dive: NonZeroU64::new(dive),
+            aim: None,
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let course = plan(Model::Direct, 15, 10, limits(8, 0)).unwrap();
+        assert_eq!(write_course(&course), "down 10\nforward 8\nforward 7\n");
+
+        // Setting the aim is only bound by the aim limit
+        let course = plan(Model::Angled, 15, 60, limits(0, 3)).unwrap();
+        assert_eq!(write_course(&course), "set aim 4\nforward 15\n");
+        let bounded = Limits {
+            aim: NonZeroU64::new(3),
+            ..limits(0, 3)
+        };
+        let course = plan(Model::Angled, 15, 60, bounded).unwrap();
+        assert_eq!(write_course(&course), "down 3\ndown 1\nforward 15\n");
+
+        let course = plan(Model::Angled, -3, 6, Limits::default()).unwrap();
+        assert_eq!(write_course(&course), "up 2\nback 3\n");
+        assert_eq!(plan(Model::Angled, 0, 0, Limits::default()), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let e = plan(Model::Angled, 4, 10, Limits::default()).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "The angled model cannot reach depth 10 at distance 4 with a single aim"
+        );
+        assert!(plan(Model::Angled, 0, 1, Limits::default()).is_err());
+        assert!(plan(Model::Angled, -1, i64::MIN, Limits::default()).is_err());
+    }
+
+    #[test]
+    fn test_largest_step() {
+        let course = plan(Model::Direct, 0, i64::MIN, Limits::default()).unwrap();
+        assert_eq!(course, vec![Command::Up(i64::MAX), Command::Up(1)]);
+    }
+
+    /// Fewest commands moving `total` in steps of at most `limit`.
+    fn fewest(total: i64, limit: Option<NonZeroU64>) -> u64 {
+        let limit = limit.map_or(u64::MAX, NonZeroU64::get);
+        total.unsigned_abs().div_ceil(limit)
+    }
+
+    fn steer<C: SubmarineController + Default>(course: &[Command]) -> Position {
+        let mut controller = C::default();
+        for &command in course {
+            controller.execute(command).unwrap();
+        }
+        controller.position()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_direct_reaches_target(
+            distance in -1000i64..1000,
+            depth in -1000i64..1000,
+            forward in 0u64..50,
+            dive in 0u64..50,
+        ) {
+            let limits = limits(forward, dive);
+            let course = plan(Model::Direct, distance, depth, limits).unwrap();
+            let end = steer::<DirectDive>(&course);
+            prop_assert_eq!((end.distance, end.depth), (distance, depth));
+
+            prop_assert_eq!(
+                course.len() as u64,
+                fewest(distance, limits.forward) + fewest(depth, limits.dive)
+            );
+        }
+
+        #[test]
+        fn prop_angled_reaches_target(
+            distance in -100i64..100,
+            aim in -100i64..100,
+            forward in 0u64..50,
+            dive in 0u64..50,
+            aim_limit in 0u64..50,
+        ) {
+            let limits = Limits {
+                aim: NonZeroU64::new(aim_limit),
+                ..limits(forward, dive)
+            };
+            let course = plan(Model::Angled, distance, aim * distance, limits).unwrap();
+            let end = steer::<AngledDive>(&course);
+            prop_assert_eq!((end.distance, end.depth), (distance, aim * distance));
+
+            // Turning takes a single command where the aim can be set
+            let aim = if distance == 0 { 0 } else { aim };
+            let turn = match fewest(aim, limits.dive) {
+                n if within(aim, limits.aim) => n.min(1),
+                n => n,
+            };
+            prop_assert_eq!(course.len() as u64, turn + fewest(distance, limits.forward));
+            let within_limits = |c: &Command| match *c {
+                Command::SetAim(a) => within(a, limits.aim),
+                Command::Down(a) | Command::Up(a) => within(a, limits.dive),
+                Command::Forward(a) | Command::Back(a) => within(a, limits.forward),
+            };
+            prop_assert!(course.iter().all(within_limits));
+            // The aim is set before moving
+            let first_move = course
+                .iter()
+                .position(|c| matches!(c, Command::Forward(_) | Command::Back(_)))
+                .unwrap_or(course.len());
+            prop_assert!(course[first_move..]
+                .iter()
+                .all(|c| matches!(c, Command::Forward(_) | Command::Back(_))));
+        }
+    }
+}
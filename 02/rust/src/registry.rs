@@ -0,0 +1,166 @@
+//! Controllers selectable by name.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::{AngledDive, DirectDive, Statement, SubmarineController};
+
+/// Makes a controller in its initial state.
+pub type Factory = fn() -> Box<dyn SubmarineController>;
+
+fn make<C: SubmarineController + Default + 'static>() -> Box<dyn SubmarineController> {
+    Box::new(C::default())
+}
+
+/// Named controllers, in the order they were registered.
+pub struct Registry {
+    controllers: Vec<(&'static str, Factory)>,
+}
+
+impl Registry {
+    /// The controllers of the puzzle, `direct` and `angled`.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register_default::<DirectDive>("direct");
+        registry.register_default::<AngledDive>("angled");
+        registry
+    }
+
+    /// A registry without any controller.
+    pub fn empty() -> Self {
+        Self {
+            controllers: Vec::new(),
+        }
+    }
+
+    /// Registers the factory under `name`, replacing the one registered
+    /// under the same name.
+    pub fn register(&mut self, name: &'static str, factory: Factory) {
+        match self.controllers.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = factory,
+            None => self.controllers.push((name, factory)),
+        }
+    }
+
+    /// Registers controllers of type `C`, starting from their default.
+    pub fn register_default<C: SubmarineController + Default + 'static>(
+        &mut self,
+        name: &'static str,
+    ) {
+        self.register(name, make::<C>);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.controllers.iter().map(|(name, _)| *name)
+    }
+
+    pub fn create(&self, name: &str) -> Result<Box<dyn SubmarineController>> {
+        let (_, factory) = self
+            .controllers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown controller {:?}, expected one of {}",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                )
+            })?;
+        Ok(factory())
+    }
+
+    /// Steers the named controller through the course.
+    pub fn course(&self, name: &str, statements: &[Statement]) -> Result<i64> {
+        let mut controller = self.create(name)?;
+        controller.follow(statements)?;
+        Ok(controller.course()?)
+    }
+
+    /// Steers each of the named controllers through the same course, with
+    /// the time it took, failing before steering any if a name is unknown.
+    pub fn courses<'a>(
+        &self,
+        names: &'a [String],
+        statements: &[Statement],
+    ) -> Result<Vec<(&'a str, i64, Duration)>> {
+        let controllers = names
+            .iter()
+            .map(|name| Ok((name.as_str(), self.create(name)?)))
+            .collect::<Result<Vec<_>>>()?;
+        controllers
+            .into_iter()
+            .map(|(name, mut controller)| {
+                let start = Instant::now();
+                controller.follow(statements)?;
+                let course = controller.course()?;
+                Ok((name, course, start.elapsed()))
+            })
+            .collect()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{checked, parse_course, Overflow, Position};
+
+    /// Moves twice as far as asked.
+    #[derive(Default)]
+    struct Turbo(DirectDive);
+
+    impl SubmarineController for Turbo {
+        fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+            self.0.dive(checked(amount.checked_mul(2))?)
+        }
+
+        fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+            self.0.forward(checked(amount.checked_mul(2))?)
+        }
+
+        fn course(&self) -> Result<i64, Overflow> {
+            self.0.course()
+        }
+
+        fn position(&self) -> Position {
+            self.0.position()
+        }
+    }
+
+    #[test]
+    fn test_courses() -> Result<()> {
+        let mut registry = Registry::default();
+        registry.register_default::<Turbo>("turbo");
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            vec!["direct", "angled", "turbo"]
+        );
+
+        let statements = parse_course("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n")?;
+        let names = ["angled", "turbo", "direct"].map(String::from);
+        assert_eq!(
+            registry
+                .courses(&names, &statements)?
+                .into_iter()
+                .map(|(name, course, _)| (name, course))
+                .collect::<Vec<_>>(),
+            vec![("angled", 900), ("turbo", 600), ("direct", 150)]
+        );
+        assert_eq!(Registry::empty().names().count(), 0);
+
+        let e = registry
+            .courses(&["direct".into(), "sideways".into()], &statements)
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            r#"Unknown controller "sideways", expected one of direct, angled, turbo"#
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! Keeping the submarine between safe depths.
+
+use thiserror::Error;
+
+use crate::{parse_course, Command, Overflow, Position, SubmarineController};
+
+/// Depths the submarine must stay between, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Envelope {
+    pub fn contains(&self, depth: i64) -> bool {
+        (self.min..=self.max).contains(&depth)
+    }
+}
+
+/// The first command taking the submarine out of its envelope.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "`{command}` on line {line} takes the submarine from depth {} to {}, out of {}..={}",
+    .before.depth, .after.depth, .envelope.min, .envelope.max
+)]
+pub struct Violation {
+    pub line: usize,
+    pub command: Command,
+    pub envelope: Envelope,
+    pub before: Position,
+    pub after: Position,
+}
+
+/// Checks that the controller it wraps stays within the envelope, keeping
+/// the first command it did not.
+#[derive(Debug)]
+pub struct Validator<C> {
+    controller: C,
+    envelope: Envelope,
+    line: usize,
+    violation: Option<Violation>,
+}
+
+impl<C> Validator<C> {
+    pub fn new(controller: C, envelope: Envelope) -> Self {
+        Self {
+            controller,
+            envelope,
+            line: 0,
+            violation: None,
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.controller
+    }
+
+    pub fn violation(&self) -> Option<&Violation> {
+        self.violation.as_ref()
+    }
+
+    /// The controller, if it stayed within the envelope.
+    pub fn check(self) -> Result<C, Violation> {
+        match self.violation {
+            Some(violation) => Err(violation),
+            None => Ok(self.controller),
+        }
+    }
+}
+
+impl<C: SubmarineController> Validator<C> {
+    fn checked(
+        &mut self,
+        command: Command,
+        steer: impl FnOnce(&mut C) -> Result<(), Overflow>,
+    ) -> Result<(), Overflow> {
+        let before = self.controller.position();
+        steer(&mut self.controller)?;
+        let after = self.controller.position();
+
+        if self.violation.is_none() && !self.envelope.contains(after.depth) {
+            self.violation = Some(Violation {
+                line: self.line,
+                command,
+                envelope: self.envelope,
+                before,
+                after,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<C: SubmarineController> SubmarineController for Validator<C> {
+    fn dive(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.dive(amount)
+    }
+
+    fn forward(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.forward(amount)
+    }
+
+    fn course(&self) -> Result<i64, Overflow> {
+        self.controller.course()
+    }
+
+    fn position(&self) -> Position {
+        self.controller.position()
+    }
+
+    fn back(&mut self, amount: i64) -> Result<(), Overflow> {
+        self.controller.back(amount)
+    }
+
+    fn set_aim(&mut self, aim: i64) -> Result<(), Overflow> {
+        self.controller.set_aim(aim)
+    }
+
+    fn execute_at(&mut self, command: Command, line: usize) -> Result<(), Overflow> {
+        self.line = line;
+        self.checked(command, |c| c.execute_at(command, line))
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), Overflow> {
+        self.checked(command, |c| c.execute(command))
+    }
+}
+
+/// Steers a new controller through the course, failing at the first
+/// command taking it out of the envelope.
+pub fn validate<C>(course: &str, envelope: Envelope) -> anyhow::Result<C>
+where
+    C: SubmarineController + Default,
+{
+    let mut validator = Validator::new(C::default(), envelope);
+    validator.follow(&parse_course(course)?)?;
+    Ok(validator.check()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AngledDive, DirectDive, Recorder};
+
+    const COURSE: &str = "forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+";
+
+    #[test]
+    fn test_within_envelope() -> anyhow::Result<()> {
+        let envelope = Envelope { min: 0, max: 60 };
+        assert_eq!(validate::<DirectDive>(COURSE, envelope)?.course(), Ok(150));
+        assert_eq!(validate::<AngledDive>(COURSE, envelope)?.course(), Ok(900));
+        Ok(())
+    }
+
+    #[test]
+    fn test_violation() {
+        let envelope = Envelope { min: 0, max: 50 };
+        let e = validate::<AngledDive>(COURSE, envelope)
+            .unwrap_err()
+            .downcast::<Violation>()
+            .unwrap();
+        assert_eq!(
+            e,
+            Violation {
+                line: 6,
+                command: Command::Forward(2),
+                envelope,
+                before: Position {
+                    distance: 13,
+                    depth: 40,
+                    aim: 10
+                },
+                after: Position {
+                    distance: 15,
+                    depth: 60,
+                    aim: 10
+                },
+            }
+        );
+        assert_eq!(
+            e.to_string(),
+            "`forward 2` on line 6 takes the submarine from depth 40 to 60, out of 0..=50"
+        );
+    }
+
+    #[test]
+    fn test_above_surface() -> anyhow::Result<()> {
+        let course = "down 2\nrepeat 3 {\n  up 1\n}\nforward 1\n";
+        let mut validator = Validator::new(
+            Recorder::<DirectDive>::default(),
+            Envelope { min: 0, max: 100 },
+        );
+        validator.follow(&parse_course(course)?)?;
+
+        let violation = validator.violation().unwrap();
+        assert_eq!(violation.line, 3);
+        assert_eq!((violation.before.depth, violation.after.depth), (0, -1));
+        // The controller is steered past the violation
+        assert_eq!(validator.into_inner().trajectory().len(), 5);
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+//! Pairs of delimiters a syntax is checked against.
+
+use thiserror::Error;
+
+use crate::{Location, ParseError, Token, TokenCategory, TokenType};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DelimiterError {
+    #[error("Empty delimiter in pair {index}")]
+    Empty { index: usize },
+
+    #[error("Delimiter {text:?} is used more than once")]
+    Duplicate { text: String },
+
+    #[error("{scores} scores given for {pairs} pairs")]
+    Scores { pairs: usize, scores: usize },
+}
+
+/// A character of the input which is no delimiter, at its position counted
+/// in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InvalidCharacter {
+    pub char: char,
+    pub position: usize,
+}
+
+impl From<InvalidCharacter> for ParseError {
+    fn from(e: InvalidCharacter) -> Self {
+        ParseError::InvalidCharacter {
+            char: e.char,
+            position: Location::at(e.position),
+        }
+    }
+}
+
+/// Points a pair is worth in the syntax checker's scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    /// Of its closer where it doesn't match
+    pub corrupted: usize,
+    /// Of its closer completing a line
+    pub incomplete: usize,
+}
+
+/// Openers and closers, which may be longer than one character.
+///
+/// Where delimiters overlap, like `<` and `<<`, the longest one is read.
+/// Delimiters starting or ending with a word character, like `begin`, are
+/// only read apart from the words around them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DelimiterSet {
+    /// Every delimiter, longest first
+    tokens: Vec<Token>,
+    /// Openers by category
+    openers: Vec<Token>,
+    /// Closers by category
+    closers: Vec<Token>,
+    /// Scores by category
+    scores: Vec<Score>,
+    /// Whether characters between delimiters are skipped instead of invalid
+    text: bool,
+}
+
+impl DelimiterSet {
+    /// Pairs scoring by their order, the first one 1 point.
+    pub fn new<O, C>(pairs: impl IntoIterator<Item = (O, C)>) -> Result<Self, DelimiterError>
+    where
+        O: Into<String>,
+        C: Into<String>,
+    {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut openers = Vec::new();
+        let mut closers = Vec::new();
+        for (index, (open, close)) in pairs.into_iter().enumerate() {
+            let cat = TokenCategory(index);
+            for (text, typ) in [
+                (open.into(), TokenType::Left),
+                (close.into(), TokenType::Right),
+            ] {
+                if text.is_empty() {
+                    return Err(DelimiterError::Empty { index });
+                }
+                if tokens.iter().any(|t| t.text == text) {
+                    return Err(DelimiterError::Duplicate { text });
+                }
+                tokens.push(Token { text, typ, cat });
+            }
+            openers.push(tokens[tokens.len() - 2].clone());
+            closers.push(tokens[tokens.len() - 1].clone());
+        }
+        tokens.sort_by_key(|t| std::cmp::Reverse(t.text.len()));
+        let scores = (1..=closers.len())
+            .map(|points| Score {
+                corrupted: points,
+                incomplete: points,
+            })
+            .collect();
+        Ok(Self {
+            tokens,
+            openers,
+            closers,
+            scores,
+            text: false,
+        })
+    }
+
+    /// `()`, `[]`, `{}` and `<>`, in this order, scoring as in the puzzle.
+    pub fn brackets() -> Self {
+        Self::new([("(", ")"), ("[", "]"), ("{", "}"), ("<", ">")])
+            .and_then(|set| {
+                set.with_scores([(3, 1), (57, 2), (1197, 3), (25137, 4)].map(
+                    |(corrupted, incomplete)| Score {
+                        corrupted,
+                        incomplete,
+                    },
+                ))
+            })
+            .unwrap()
+    }
+
+    /// Scores of the pairs, in their order.
+    pub fn with_scores(
+        mut self,
+        scores: impl IntoIterator<Item = Score>,
+    ) -> Result<Self, DelimiterError> {
+        let scores = scores.into_iter().collect::<Vec<_>>();
+        if scores.len() != self.len() {
+            return Err(DelimiterError::Scores {
+                pairs: self.len(),
+                scores: scores.len(),
+            });
+        }
+        self.scores = scores;
+        Ok(self)
+    }
+
+    /// Skips the characters between delimiters instead of rejecting them.
+    pub fn allow_text(mut self) -> Self {
+        self.text = true;
+        self
+    }
+
+    /// Number of pairs.
+    pub fn len(&self) -> usize {
+        self.closers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.closers.is_empty()
+    }
+
+    /// The opener or closer written as `text`.
+    pub fn token(&self, text: &str) -> Option<Token> {
+        self.tokens.iter().find(|t| t.text == text).cloned()
+    }
+
+    pub(crate) fn opener(&self, cat: TokenCategory) -> &Token {
+        &self.openers[cat.0]
+    }
+
+    pub(crate) fn closer(&self, cat: TokenCategory) -> &Token {
+        &self.closers[cat.0]
+    }
+
+    pub(crate) fn score(&self, cat: TokenCategory) -> Score {
+        self.scores[cat.0]
+    }
+
+    /// Whether `token` is written at the start of `rest`, which follows
+    /// `prev`, apart from the words around it.
+    fn reads(token: &Token, prev: Option<char>, rest: &str) -> bool {
+        let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        rest.starts_with(&token.text)
+            && !(word(token.text.chars().next()) && word(prev))
+            && !(word(token.text.chars().next_back())
+                && word(rest[token.text.len()..].chars().next()))
+    }
+
+    /// The delimiters of `input` with their positions, counted in characters,
+    /// and the characters that are none.
+    pub(crate) fn tokens<'a>(
+        &'a self,
+        input: &'a str,
+    ) -> impl Iterator<Item = Result<(usize, Token), InvalidCharacter>> + 'a {
+        let mut rest = input;
+        let mut position = 0;
+        let mut prev = None;
+        std::iter::from_fn(move || loop {
+            let c = rest.chars().next()?;
+            let start = position;
+            match self.tokens.iter().find(|t| Self::reads(t, prev, rest)) {
+                Some(t) => {
+                    position += t.text.chars().count();
+                    prev = t.text.chars().next_back();
+                    rest = &rest[t.text.len()..];
+                    return Some(Ok((start, t.clone())));
+                }
+                None => {
+                    position += 1;
+                    prev = Some(c);
+                    rest = &rest[c.len_utf8()..];
+                    if !self.text {
+                        return Some(Err(InvalidCharacter {
+                            char: c,
+                            position: start,
+                        }));
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl Default for DelimiterSet {
+    fn default() -> Self {
+        Self::brackets()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Syntax;
+
+    fn template() -> DelimiterSet {
+        DelimiterSet::new([("begin", "end"), ("/*", "*/"), ("(", ")")])
+            .unwrap()
+            .allow_text()
+    }
+
+    #[test]
+    fn test_multi_character() {
+        let set = template();
+        let parsed = Syntax::parse_with("begin f(x) /* é */ end", &set).unwrap();
+        assert_eq!(
+            parsed.tokens.iter().map(Token::text).collect::<Vec<_>>(),
+            vec!["begin", "(", ")", "/*", "*/", "end"]
+        );
+
+        let parsed = Syntax::parse_with("begin /* (", &set).unwrap();
+        assert_eq!(parsed.completion(), ")*/end");
+
+        assert_eq!(
+            Syntax::parse_with("begin é(end", &set).unwrap_err(),
+            ParseError::UnmatchedToken {
+                token: set.token("end").unwrap(),
+                prev: Some((set.token("(").unwrap(), Location::at(7))),
+                position: Location::at(8)
+            }
+        );
+    }
+
+    #[test]
+    fn test_word_boundaries() {
+        let set = template();
+        let parsed = Syntax::parse_with("begin append(x) end", &set).unwrap();
+        assert_eq!(
+            parsed.tokens.iter().map(Token::text).collect::<Vec<_>>(),
+            vec!["begin", "(", ")", "end"]
+        );
+        assert!(Syntax::parse_with("beginning", &set)
+            .unwrap()
+            .tokens
+            .is_empty());
+        let parsed = Syntax::parse_with("(begin end)", &set).unwrap();
+        assert_eq!(parsed.completion(), "");
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let set = DelimiterSet::new([("<", ">"), ("<<", ">>")]).unwrap();
+        let parsed = Syntax::parse_with("<<>><>", &set).unwrap();
+        assert_eq!(parsed.tokens.len(), 4);
+        assert!(parsed.completions.is_empty());
+        assert_eq!(
+            Syntax::parse_with("< >", &set).unwrap_err(),
+            ParseError::InvalidCharacter {
+                char: ' ',
+                position: Location::at(1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_set() {
+        assert_eq!(
+            DelimiterSet::new([("(", ")"), ("", "]")]),
+            Err(DelimiterError::Empty { index: 1 })
+        );
+        assert_eq!(
+            DelimiterSet::new([("|", "|")]).unwrap_err().to_string(),
+            r#"Delimiter "|" is used more than once"#
+        );
+        assert_eq!(
+            DelimiterSet::brackets().with_scores([]),
+            Err(DelimiterError::Scores {
+                pairs: 4,
+                scores: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Where errors are, and how to show them.
+
+use std::fmt::Display;
+
+use crate::{ParseError, Token};
+
+/// A position in the input, both counted from 1, columns in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// The location of the character at `position` of the first line,
+    /// counted from 0.
+    pub(crate) fn at(position: usize) -> Self {
+        Self {
+            line: 1,
+            column: position + 1,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+pub(crate) fn describe_prev(prev: &Option<(Token, Location)>) -> String {
+    match prev {
+        Some((token, at)) => format!("doesn't match `{}` at {}", token.text(), at),
+        None => "has nothing to close".into(),
+    }
+}
+
+impl ParseError {
+    pub fn position(&self) -> Location {
+        match self {
+            ParseError::InvalidCharacter { position, .. }
+            | ParseError::UnmatchedToken { position, .. } => *position,
+        }
+    }
+
+    /// The error moved to `line`, from a line parsed on its own.
+    pub fn on_line(mut self, line: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidCharacter { position, .. } => position.line = line,
+            ParseError::UnmatchedToken { position, prev, .. } => {
+                position.line = line;
+                if let Some((_, at)) = prev {
+                    at.line = line;
+                }
+            }
+        }
+        self
+    }
+
+    /// The error moved right by `columns`, from a line parsed without its
+    /// indentation.
+    pub fn indented(mut self, columns: usize) -> Self {
+        match &mut self {
+            ParseError::InvalidCharacter { position, .. } => position.column += columns,
+            ParseError::UnmatchedToken { position, prev, .. } => {
+                position.column += columns;
+                if let Some((_, at)) = prev {
+                    at.column += columns;
+                }
+            }
+        }
+        self
+    }
+
+    /// The error pointing at its line of `input`, as errors of the other
+    /// days do.
+    pub fn snippet(&self, input: &str) -> aoc_common::ParseError {
+        let position = self.position();
+        let source = input.lines().nth(position.line - 1).unwrap_or_default();
+        let (width, label) = match self {
+            ParseError::InvalidCharacter { .. } => (1, "not a delimiter"),
+            ParseError::UnmatchedToken { token, prev, .. } => (
+                token.text().chars().count(),
+                match prev {
+                    Some(_) => "mismatched closer",
+                    None => "nothing to close",
+                },
+            ),
+        };
+        let snippet = aoc_common::ParseError {
+            message: self.to_string(),
+            file: None,
+            line: position.line,
+            column: position.column,
+            text: source
+                .chars()
+                .skip(position.column - 1)
+                .take(width)
+                .collect(),
+            source_line: source.into(),
+            labels: None,
+        }
+        .with_label(label);
+        match self {
+            ParseError::UnmatchedToken {
+                prev: Some((open, at)),
+                ..
+            } => snippet.with_secondary(at.column, open.text().chars().count(), "opened here"),
+            _ => snippet,
+        }
+    }
+
+    /// Shows the error under its line of `input`, like rustc does.
+    ///
+    /// ```text
+    /// Token `)` at 3:6 doesn't match `<` at 3:5
+    ///  --> <input>:3:6
+    ///   |
+    /// 3 | (([]<)>
+    ///   |     -^ mismatched closer
+    ///   |     |
+    ///   |     opened here
+    /// ```
+    pub fn render(&self, input: &str) -> String {
+        self.snippet(input).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::{DelimiterSet, Syntax};
+
+    #[test]
+    fn test_render() {
+        let input = "()\n\n(([]<)>\n";
+        let e = Syntax::parse("(([]<)>").unwrap_err().on_line(3);
+        assert_eq!(
+            e.render(input),
+            indoc! {"
+                Token `)` at 3:6 doesn't match `<` at 3:5
+                 --> <input>:3:6
+                  |
+                3 | (([]<)>
+                  |     -^ mismatched closer
+                  |     |
+                  |     opened here"}
+        );
+
+        let e = Syntax::parse("()x").unwrap_err();
+        assert_eq!(
+            e.render("()x"),
+            indoc! {"
+                Invalid character 'x' at 1:3
+                 --> <input>:1:3
+                  |
+                1 | ()x
+                  |   ^ not a delimiter"}
+        );
+    }
+
+    #[test]
+    fn test_render_wide() {
+        let set = DelimiterSet::new([("begin", "end"), ("(", ")")])
+            .unwrap()
+            .allow_text();
+        let line = "x = begin f(y end";
+        let e = Syntax::parse_with(line, &set).unwrap_err().on_line(12);
+        assert_eq!(
+            e.render(&format!("{}{}", "\n".repeat(11), line)),
+            indoc! {"
+                Token `end` at 12:15 doesn't match `(` at 12:12
+                  --> <input>:12:15
+                   |
+                12 | x = begin f(y end
+                   |            -  ^^^ mismatched closer
+                   |            |
+                   |            opened here"}
+        );
+
+        let e = Syntax::parse_with(") end", &set).unwrap_err();
+        assert_eq!(e.to_string(), "Token `)` at 1:1 has nothing to close");
+        assert!(e
+            .render(") end")
+            .ends_with("1 | ) end\n  | ^ nothing to close"));
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt::Debug;
+
+use aoc_common::{Answer, Solution};
+use thiserror::Error;
+
+mod delimiter;
+mod diagnostic;
+mod recover;
+mod repair;
+mod tree;
+
+pub use delimiter::{DelimiterError, DelimiterSet, Score};
+pub use diagnostic::Location;
+pub use recover::{Recovered, Recovery};
+pub use repair::{repair, Edit, Repair};
+pub use tree::{Group, Tree};
+
+use tree::Matcher;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Result<Syntax, ParseError>>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_lines(input))
+    }
+
+    fn part1(parsed: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(score_corrupted(parsed, &DelimiterSet::brackets()).into())
+    }
+
+    fn part2(parsed: &Self::Input) -> anyhow::Result<Answer> {
+        let score = score_incomplete(parsed, &DelimiterSet::brackets())
+            .ok_or_else(|| anyhow::anyhow!("No line parsed without errors"))?;
+        Ok(score.into())
+    }
+}
+
+/// Parses the lines which are not blank, without the whitespace around
+/// them, numbering the errors as in the input.
+fn parse_lines(input: &str) -> Vec<Result<Syntax, ParseError>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+            Syntax::parse(line.trim()).map_err(|e| e.on_line(i + 1).indented(indent))
+        })
+        .collect()
+}
+
+/// Scores the first unmatched closer of each line by its pair in
+/// `delimiters`.
+fn score_corrupted(parsed: &[Result<Syntax, ParseError>], delimiters: &DelimiterSet) -> usize {
+    parsed
+        .iter()
+        .filter_map(|r| match r {
+            Err(ParseError::UnmatchedToken { token, .. }) => {
+                Some(delimiters.score(token.cat).corrupted)
+            }
+            _ => None,
+        })
+        .sum()
+}
+
+/// Scores the completions of each line by their pairs in `delimiters`,
+/// keeping the middle score, if any line parsed.
+fn score_incomplete(
+    parsed: &[Result<Syntax, ParseError>],
+    delimiters: &DelimiterSet,
+) -> Option<usize> {
+    let mut scores = parsed
+        .iter()
+        .filter_map(|r| match r {
+            Ok(Syntax { completions, .. }) => {
+                let score = completions
+                    .iter()
+                    .map(|t| delimiters.score(t.cat).incomplete)
+                    .fold(0, |a, v| a * 5 + v);
+                Some(score)
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    scores.sort_unstable();
+    scores.get(scores.len() / 2).copied()
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Token {
+    text: String,
+    typ: TokenType,
+    cat: TokenCategory,
+}
+
+impl Token {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TokenType {
+    Left,
+    Right,
+}
+
+/// The index of a pair in its `DelimiterSet`.
+#[derive(Clone, Copy, PartialEq)]
+struct TokenCategory(usize);
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+#[derive(Debug)]
+pub struct Syntax {
+    pub tokens: Vec<Token>,
+    /// Closers of the groups left open, innermost first
+    pub completions: Vec<Token>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ParseError {
+    #[error("Invalid character {char:?} at {position}")]
+    InvalidCharacter { char: char, position: Location },
+
+    #[error("Token `{}` at {} {}", .token.text(), .position, diagnostic::describe_prev(.prev))]
+    UnmatchedToken {
+        token: Token,
+        /// The opener of the group the token should have closed
+        prev: Option<(Token, Location)>,
+        position: Location,
+    },
+}
+
+impl Syntax {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::parse_with(input, &DelimiterSet::default())
+    }
+
+    pub fn parse_with(input: &str, delimiters: &DelimiterSet) -> Result<Self, ParseError> {
+        Ok(Matcher::read(input, delimiters)?.syntax(delimiters))
+    }
+
+    /// The text closing the groups left open.
+    pub fn completion(&self) -> String {
+        self.completions.iter().map(Token::text).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_syntax_parsing() {
+        assert!(Syntax::parse("()[]{}<>").is_ok());
+        assert!(Syntax::parse("([<>]){}").is_ok());
+        assert_eq!(
+            Syntax::parse("()foo").unwrap_err(),
+            ParseError::InvalidCharacter {
+                char: 'f',
+                position: Location { line: 1, column: 3 }
+            }
+        );
+        assert_eq!(
+            Syntax::parse("]").unwrap_err(),
+            ParseError::UnmatchedToken {
+                token: DelimiterSet::default().token("]").unwrap(),
+                prev: None,
+                position: Location { line: 1, column: 1 }
+            }
+        );
+        assert_eq!(
+            Syntax::parse("(]").unwrap_err(),
+            ParseError::UnmatchedToken {
+                token: DelimiterSet::default().token("]").unwrap(),
+                prev: Some((
+                    DelimiterSet::default().token("(").unwrap(),
+                    Location { line: 1, column: 1 }
+                )),
+                position: Location { line: 1, column: 2 }
+            }
+        );
+        assert_eq!(
+            Syntax::parse("(([]<)>").unwrap_err(),
+            ParseError::UnmatchedToken {
+                token: DelimiterSet::default().token(")").unwrap(),
+                prev: Some((
+                    DelimiterSet::default().token("<").unwrap(),
+                    Location { line: 1, column: 5 }
+                )),
+                position: Location { line: 1, column: 6 }
+            }
+        );
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let parsed = parse_lines("\n\n()\n\n(x\n[>\n");
+        let lines = parsed
+            .iter()
+            .map(|r| r.as_ref().err().map(|e| e.position().line))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![None, Some(5), Some(6)]);
+
+        let e = parse_lines("\n  (]\n").remove(0).unwrap_err();
+        assert_eq!(e.to_string(), "Token `]` at 2:4 doesn't match `(` at 2:3");
+        assert!(e
+            .render("\n  (]\n")
+            .ends_with("2 |   (]\n  |   -^ mismatched closer\n  |   |\n  |   opened here"));
+        match &parsed[2] {
+            Err(ParseError::UnmatchedToken {
+                prev: Some((_, at)),
+                ..
+            }) => assert_eq!(*at, Location { line: 6, column: 1 }),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_completions() {
+        let parsed = Syntax::parse("[({(<(())[]>[[{[]{<()<>>").unwrap();
+        assert_eq!(parsed.completion(), "}}]])})]");
+    }
+
+    #[test]
+    fn test_scoring() {
+        let parsed = parse_lines(indoc! {"
+            [({(<(())[]>[[{[]{<()<>>
+            [(()[<>])]({[<{<<[]>>(
+            {([(<{}[<>[]}>{[]{[(<()>
+            (((({<>}<{<{<>}{[]{[]{}
+            [[<[([]))<([[{}[[()]]]
+            [{[{({}]{}}([{[{{{}}([]
+            {<[[]]>}<{[{[{[]{()[[[]
+            [<(<(<(<{}))><([]([]()
+            <{([([[(<>()){}]>(<<{{
+            <{([{{}}[<[[[<>{}]]]>[]]
+        "});
+
+        let brackets = DelimiterSet::brackets();
+        assert_eq!(score_corrupted(&parsed, &brackets), 26397);
+        assert_eq!(score_incomplete(&parsed, &brackets), Some(288957));
+
+        let parsed = parse_lines("(]\n<)\n");
+        assert_eq!(score_incomplete(&parsed, &brackets), None);
+        assert!(Day10::part2(&parsed).is_err());
+    }
+
+    #[test]
+    fn test_scoring_more_pairs() {
+        let set = DelimiterSet::new([("(", ")"), ("[", "]"), ("{", "}"), ("<", ">"), ("«", "»")])
+            .unwrap();
+        let parsed = ["(«]", "(«", "«»"]
+            .iter()
+            .map(|line| Syntax::parse_with(line, &set))
+            .collect::<Vec<_>>();
+        assert_eq!(score_corrupted(&parsed, &set), 2);
+        assert_eq!(score_incomplete(&parsed, &set), Some(26));
+    }
+}
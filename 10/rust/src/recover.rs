@@ -0,0 +1,121 @@
+//! Parsing past the errors of a line.
+
+use crate::{tree::Matcher, DelimiterSet, ParseError, Syntax};
+
+/// How parsing resumes after an unmatched closer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Drops the closer, leaving the open groups as they were
+    Skip,
+    /// Closes the groups opened after the opener of the same pair, as if
+    /// their closers were missing, or drops the closer when there is none
+    Insert,
+}
+
+/// A line parsed with every error it has.
+#[derive(Debug)]
+pub struct Recovered {
+    /// The tokens read and the closers of the groups still open at the end
+    pub syntax: Syntax,
+    pub errors: Vec<ParseError>,
+}
+
+impl Recovered {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Syntax {
+    /// Parses the line like `parse_with`, resuming after errors as told by
+    /// `recovery`. Invalid characters are skipped.
+    pub fn parse_recovering(
+        input: &str,
+        delimiters: &DelimiterSet,
+        recovery: Recovery,
+    ) -> Recovered {
+        let mut matcher = Matcher::default();
+        let mut errors = Vec::new();
+        for token in delimiters.tokens(input) {
+            let (p, t) = match token {
+                Ok(token) => token,
+                Err(e) => {
+                    errors.push(e.into());
+                    continue;
+                }
+            };
+            if let Err(e) = matcher.push(p, t.clone()) {
+                errors.push(e);
+                if recovery == Recovery::Insert {
+                    matcher.close_outer(p, t);
+                }
+            }
+        }
+
+        Recovered {
+            syntax: matcher.syntax(delimiters),
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Location;
+
+    fn positions(recovered: &Recovered) -> Vec<usize> {
+        recovered
+            .errors
+            .iter()
+            .map(|e| e.position().column - 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_skip() {
+        let brackets = DelimiterSet::brackets();
+        let recovered = Syntax::parse_recovering("(([]<)>]", &brackets, Recovery::Skip);
+        assert_eq!(positions(&recovered), vec![5, 7]);
+        assert_eq!(
+            recovered.errors[1],
+            ParseError::UnmatchedToken {
+                token: brackets.token("]").unwrap(),
+                prev: Some((brackets.token("(").unwrap(), Location::at(1))),
+                position: Location::at(7)
+            }
+        );
+        assert_eq!(recovered.syntax.completion(), "))");
+    }
+
+    #[test]
+    fn test_insert() {
+        let brackets = DelimiterSet::brackets();
+        let recovered = Syntax::parse_recovering("(([]<)>]", &brackets, Recovery::Insert);
+        assert_eq!(positions(&recovered), vec![5, 6, 7]);
+        assert_eq!(recovered.syntax.completion(), ")");
+
+        let recovered =
+            Syntax::parse_recovering("{([(<{}[<>[]}>{[]{[(<()>", &brackets, Recovery::Insert);
+        assert_eq!(positions(&recovered), vec![12, 13]);
+        assert_eq!(recovered.syntax.completion(), ")]}}");
+    }
+
+    #[test]
+    fn test_first_error() {
+        let brackets = DelimiterSet::brackets();
+        for line in ["[({(<(())[]>[[{[]{<()<>>", "[<(<(<(<{}))><([]([]()", "()x]"] {
+            let recovered = Syntax::parse_recovering(line, &brackets, Recovery::Skip);
+            match Syntax::parse(line) {
+                Ok(syntax) => {
+                    assert!(recovered.is_ok());
+                    assert_eq!(recovered.syntax.completions, syntax.completions);
+                }
+                Err(e) => assert_eq!(recovered.errors[0], e),
+            }
+        }
+        let recovered = Syntax::parse_recovering("(x]y", &brackets, Recovery::Skip);
+        assert_eq!(positions(&recovered), vec![1, 2, 3]);
+        assert_eq!(recovered.syntax.completion(), ")");
+    }
+}
@@ -0,0 +1,334 @@
+//! Fewest edits balancing a line.
+
+use std::{collections::HashSet, fmt::Display, ops::Range};
+
+use crate::{DelimiterSet, Token, TokenType};
+
+/// A change to a line, at a position of the original line counted in
+/// characters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    /// Inserts the token before the character at `position`
+    Insert { position: usize, token: Token },
+    /// Deletes a character which is not a delimiter
+    Delete { position: usize, char: char },
+    /// Replaces the delimiter at `position` with another one
+    Replace {
+        position: usize,
+        from: Token,
+        to: Token,
+    },
+}
+
+impl Display for Edit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Edit::Insert { position, token } => {
+                write!(f, "insert `{}` at {}", token.text(), position)
+            }
+            Edit::Delete { position, char } => write!(f, "delete `{}` at {}", char, position),
+            Edit::Replace { position, from, to } => write!(
+                f,
+                "replace `{}` with `{}` at {}",
+                from.text(),
+                to.text(),
+                position
+            ),
+        }
+    }
+}
+
+/// A balanced version of a line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub line: String,
+    pub edits: Vec<Edit>,
+    /// Original and repaired text of consecutive parts of the line
+    segments: Vec<(String, String)>,
+}
+
+impl Repair {
+    /// The original and the repaired line aligned above each other, with
+    /// the changes marked below them as `+` for insertions, `-` for
+    /// deletions and `~` for replacements.
+    pub fn diff(&self) -> String {
+        let mut rows = [String::from("-"), String::from("+"), String::from(" ")];
+        for (from, to) in &self.segments {
+            let width = from.chars().count().max(to.chars().count());
+            let mark = match (from.is_empty(), to.is_empty()) {
+                _ if from == to => ' ',
+                (true, _) => '+',
+                (_, true) => '-',
+                _ => '~',
+            };
+            rows[0] += &format!("{:<1$}", from, width);
+            rows[1] += &format!("{:<1$}", to, width);
+            rows[2].extend(std::iter::repeat_n(mark, width));
+        }
+        rows.iter().map(|r| format!("{}\n", r.trim_end())).collect()
+    }
+}
+
+/// How the tokens of a range are balanced the cheapest.
+#[derive(Clone, Copy)]
+enum Choice {
+    Empty,
+    /// Inserts the partner of the first token
+    Insert,
+    /// Matches the first token with the given one
+    Pair(usize),
+}
+
+/// Replacements making `left` and `right` a pair.
+fn pair(left: &Token, right: &Token, delimiters: &DelimiterSet) -> (Option<Token>, Option<Token>) {
+    let opener = || delimiters.opener(left.cat).clone();
+    let closer = || delimiters.closer(left.cat).clone();
+    match (left.typ, right.typ) {
+        (TokenType::Left, TokenType::Right) if left.cat == right.cat => (None, None),
+        (TokenType::Left, _) => (None, Some(closer())),
+        (TokenType::Right, TokenType::Right) => (Some(delimiters.opener(right.cat).clone()), None),
+        (TokenType::Right, TokenType::Left) => (Some(opener()), Some(closer())),
+    }
+}
+
+/// The fewest edits balancing the line, keeping the text between its
+/// delimiters.
+///
+/// Deleting a delimiter takes as many edits as inserting its partner, so
+/// partners are inserted instead, keeping every delimiter of the line.
+/// Characters which are not delimiters of the set are deleted, unless it
+/// allows text. Takes cubic time in the number of delimiters.
+pub fn repair(input: &str, delimiters: &DelimiterSet) -> Repair {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut invalid = HashSet::new();
+    for token in delimiters.tokens(input) {
+        match token {
+            Ok(token) => tokens.push(token),
+            Err(e) => {
+                invalid.insert(e.position);
+            }
+        }
+    }
+
+    // cost[i][j] is the fewest edits balancing tokens i..j
+    let n = tokens.len();
+    let mut cost = vec![vec![0; n + 1]; n + 1];
+    let mut choice = vec![vec![Choice::Empty; n + 1]; n + 1];
+    for len in 1..=n {
+        for i in 0..=n - len {
+            let j = i + len;
+            let mut best = (1 + cost[i + 1][j], Choice::Insert);
+            for k in i + 1..j {
+                let (l, r) = pair(&tokens[i].1, &tokens[k].1, delimiters);
+                let c =
+                    l.is_some() as usize + r.is_some() as usize + cost[i + 1][k] + cost[k + 1][j];
+                if c < best.0 || (c == best.0 && matches!(best.1, Choice::Insert)) {
+                    best = (c, Choice::Pair(k));
+                }
+            }
+            cost[i][j] = best.0;
+            choice[i][j] = best.1;
+        }
+    }
+
+    let mut balancing = Balancing {
+        tokens: &tokens,
+        choice: &choice,
+        delimiters,
+        replaced: vec![None; n],
+        inserted: vec![Vec::new(); n + 1],
+    };
+    balancing.rebuild(0, n);
+    let Balancing {
+        mut replaced,
+        mut inserted,
+        ..
+    } = balancing;
+
+    let mut segments = Vec::new();
+    let mut edits = Vec::new();
+    let keep = |range: Range<usize>, segments: &mut Vec<_>, edits: &mut Vec<_>| {
+        for position in range {
+            let c = chars[position];
+            if invalid.contains(&position) {
+                edits.push(Edit::Delete { position, char: c });
+                push(segments, c.to_string(), String::new());
+            } else {
+                push(segments, c.to_string(), c.to_string());
+            }
+        }
+    };
+    let mut cursor = 0;
+    for t in 0..=n {
+        let position = tokens.get(t).map_or(chars.len(), |(p, _)| *p);
+        // Closers go before the spaces ending a group, openers right before
+        // the closer they are inserted for
+        let spaces = chars[cursor..position]
+            .iter()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        keep(cursor..position - spaces, &mut segments, &mut edits);
+        let (openers, closers): (Vec<_>, Vec<_>) = inserted[t]
+            .drain(..)
+            .partition(|token| token.typ == TokenType::Left);
+        for token in closers {
+            push(&mut segments, String::new(), token.text.clone());
+            edits.push(Edit::Insert {
+                position: position - spaces,
+                token,
+            });
+        }
+        keep(position - spaces..position, &mut segments, &mut edits);
+        for token in openers {
+            push(&mut segments, String::new(), token.text.clone());
+            edits.push(Edit::Insert { position, token });
+        }
+        if let Some((_, from)) = tokens.get(t) {
+            match replaced[t].take() {
+                Some(to) => {
+                    push(&mut segments, from.text.clone(), to.text.clone());
+                    edits.push(Edit::Replace {
+                        position,
+                        from: from.clone(),
+                        to,
+                    });
+                }
+                None => push(&mut segments, from.text.clone(), from.text.clone()),
+            }
+            cursor = position + from.text.chars().count();
+        }
+    }
+
+    Repair {
+        line: segments.iter().map(|(_, to)| to.as_str()).collect(),
+        edits,
+        segments,
+    }
+}
+
+/// Adds a part of the line, merged with the previous one when both are
+/// unchanged.
+fn push(segments: &mut Vec<(String, String)>, from: String, to: String) {
+    match segments.last_mut() {
+        Some((f, t)) if f == t && from == to => {
+            f.push_str(&from);
+            t.push_str(&to);
+        }
+        _ => segments.push((from, to)),
+    }
+}
+
+/// The edits of the cheapest choices, by token.
+struct Balancing<'a> {
+    tokens: &'a [(usize, Token)],
+    choice: &'a [Vec<Choice>],
+    delimiters: &'a DelimiterSet,
+    replaced: Vec<Option<Token>>,
+    /// Tokens inserted before each token, and at the end
+    inserted: Vec<Vec<Token>>,
+}
+
+impl Balancing<'_> {
+    /// Rebuilds the edits of tokens `i..j`, left to right so that the
+    /// tokens inserted at the same place are in order.
+    fn rebuild(&mut self, i: usize, j: usize) {
+        match self.choice[i][j] {
+            Choice::Empty => {}
+            Choice::Insert => {
+                let t = &self.tokens[i].1;
+                match t.typ {
+                    TokenType::Left => {
+                        self.rebuild(i + 1, j);
+                        self.inserted[j].push(self.delimiters.closer(t.cat).clone());
+                    }
+                    TokenType::Right => {
+                        self.inserted[i].push(self.delimiters.opener(t.cat).clone());
+                        self.rebuild(i + 1, j);
+                    }
+                }
+            }
+            Choice::Pair(k) => {
+                let (l, r) = pair(&self.tokens[i].1, &self.tokens[k].1, self.delimiters);
+                self.replaced[i] = l;
+                self.replaced[k] = r;
+                self.rebuild(i + 1, k);
+                self.rebuild(k + 1, j);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Syntax;
+
+    #[test]
+    fn test_repair() {
+        let brackets = DelimiterSet::brackets();
+        let repaired = repair("(]", &brackets);
+        assert_eq!(repaired.line, "()");
+        assert_eq!(
+            repaired.edits,
+            vec![Edit::Replace {
+                position: 1,
+                from: brackets.token("]").unwrap(),
+                to: brackets.token(")").unwrap()
+            }]
+        );
+
+        assert_eq!(repair("(((", &brackets).line, "()()");
+        assert_eq!(repair("]", &brackets).line, "[]");
+        assert_eq!(repair("([]{}<>)", &brackets).edits, vec![]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let brackets = DelimiterSet::brackets();
+        let repaired = repair("[[<[([]))<([[{}[[()]]]", &brackets);
+        assert_eq!(
+            repaired
+                .edits
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "replace `[` with `]` at 1",
+                "replace `)` with `]` at 8",
+                "replace `<` with `>` at 9",
+                "replace `[` with `)` at 11"
+            ]
+        );
+        assert_eq!(
+            repaired.diff(),
+            "-[[<[([]))<([[{}[[()]]]\n+[]<[([])]>()[{}[[()]]]\n  ~      ~~ ~\n"
+        );
+        assert!(Syntax::parse(&repaired.line)
+            .unwrap()
+            .completions
+            .is_empty());
+
+        let repaired = repair("(x]", &brackets);
+        assert_eq!(repaired.line, "()");
+        assert_eq!(repaired.diff(), "-(x]\n+( )\n  -~\n");
+    }
+
+    #[test]
+    fn test_multi_character() {
+        let set = DelimiterSet::new([("begin", "end"), ("(", ")")])
+            .unwrap()
+            .allow_text();
+        let repaired = repair("begin f(x end", &set);
+        assert_eq!(repaired.line, "begin f(x) end");
+        assert_eq!(
+            repaired.diff(),
+            "-begin f(x  end\n+begin f(x) end\n          +\n"
+        );
+        assert!(Syntax::parse_with(&repaired.line, &set)
+            .unwrap()
+            .completions
+            .is_empty());
+    }
+}
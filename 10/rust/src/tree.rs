@@ -0,0 +1,275 @@
+//! Nesting of the groups of a line.
+
+use std::ops::Range;
+
+use crate::{DelimiterSet, Location, ParseError, Syntax, Token, TokenType};
+
+/// Delimiters and what is between them, at positions counted in
+/// characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub open: Token,
+    /// `None` when the line ends before the group is closed
+    pub close: Option<Token>,
+    /// Position of the opener
+    pub start: usize,
+    /// Position after the closer, or the end of the line
+    pub end: usize,
+    /// 1 for groups which are not in another one
+    pub depth: usize,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+impl Group {
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Position of the closer, if there is one.
+    pub fn close_at(&self) -> Option<usize> {
+        self.close
+            .as_ref()
+            .map(|t| self.end - t.text.chars().count())
+    }
+}
+
+/// Groups of a line, referred to by their index in the order they open.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tree {
+    groups: Vec<Group>,
+    roots: Vec<usize>,
+}
+
+impl Tree {
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    pub fn group(&self, id: usize) -> &Group {
+        &self.groups[id]
+    }
+
+    /// Groups which are not in another one.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Deepest nesting of groups, 0 for a line without any.
+    pub fn depth(&self) -> usize {
+        self.groups.iter().map(|g| g.depth).max().unwrap_or(0)
+    }
+
+    /// The innermost group around `position`, including its delimiters.
+    pub fn enclosing(&self, position: usize) -> Option<usize> {
+        let mut found = None;
+        let mut candidates = &self.roots;
+        while let Some(&id) = candidates
+            .iter()
+            .find(|&&id| self.groups[id].span().contains(&position))
+        {
+            found = Some(id);
+            candidates = &self.groups[id].children;
+        }
+        found
+    }
+
+    /// Position of the delimiter matching the one at `position`.
+    pub fn matching(&self, position: usize) -> Option<usize> {
+        let group = &self.groups[self.enclosing(position)?];
+        let close_at = group.close_at()?;
+        if position < group.start + group.open.text.chars().count() {
+            Some(close_at)
+        } else if position >= close_at {
+            Some(group.start)
+        } else {
+            None
+        }
+    }
+
+    /// The group and the ones next to it, in order.
+    pub fn siblings(&self, id: usize) -> &[usize] {
+        match self.groups[id].parent {
+            Some(parent) => &self.groups[parent].children,
+            None => &self.roots,
+        }
+    }
+
+    pub fn next_sibling(&self, id: usize) -> Option<usize> {
+        let siblings = self.siblings(id);
+        let i = siblings.iter().position(|&s| s == id)?;
+        siblings.get(i + 1).copied()
+    }
+
+    pub fn previous_sibling(&self, id: usize) -> Option<usize> {
+        let siblings = self.siblings(id);
+        let i = siblings.iter().position(|&s| s == id)?;
+        i.checked_sub(1).map(|i| siblings[i])
+    }
+}
+
+/// Delimiters of a line read one at a time, each closer matched with the
+/// innermost group still open.
+#[derive(Debug, Default)]
+pub(crate) struct Matcher {
+    tree: Tree,
+    /// Groups still open, innermost last
+    open: Vec<usize>,
+    /// Every delimiter read, whether it matched or not
+    tokens: Vec<Token>,
+}
+
+impl Matcher {
+    /// Reads every delimiter of the line, stopping at the first error.
+    pub(crate) fn read(input: &str, delimiters: &DelimiterSet) -> Result<Self, ParseError> {
+        let mut matcher = Self::default();
+        for token in delimiters.tokens(input) {
+            let (p, t) = token?;
+            matcher.push(p, t)?;
+        }
+        Ok(matcher)
+    }
+
+    /// Reads the delimiter at `position`, failing if it is a closer which
+    /// doesn't match the innermost open group.
+    pub(crate) fn push(&mut self, position: usize, token: Token) -> Result<(), ParseError> {
+        self.tokens.push(token.clone());
+        match token.typ {
+            TokenType::Left => {
+                let id = self.tree.groups.len();
+                let parent = self.open.last().copied();
+                match parent {
+                    Some(parent) => self.tree.groups[parent].children.push(id),
+                    None => self.tree.roots.push(id),
+                }
+                self.tree.groups.push(Group {
+                    open: token,
+                    close: None,
+                    start: position,
+                    end: position,
+                    depth: self.open.len() + 1,
+                    parent,
+                    children: Vec::new(),
+                });
+                self.open.push(id);
+            }
+            TokenType::Right => {
+                let prev = self.open.last().map(|&id| &self.tree.groups[id]);
+                match prev {
+                    Some(group) if group.open.cat == token.cat => self.close(position, token),
+                    _ => {
+                        return Err(ParseError::UnmatchedToken {
+                            prev: prev.map(|g| (g.open.clone(), Location::at(g.start))),
+                            token,
+                            position: Location::at(position),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the innermost open group the closer at `position` matches,
+    /// leaving the ones inside it unclosed. Returns whether there was one.
+    pub(crate) fn close_outer(&mut self, position: usize, token: Token) -> bool {
+        let groups = &self.tree.groups;
+        match self
+            .open
+            .iter()
+            .rposition(|&id| groups[id].open.cat == token.cat)
+        {
+            Some(i) => {
+                for id in self.open.drain(i + 1..) {
+                    self.tree.groups[id].end = position;
+                }
+                self.close(position, token);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn close(&mut self, position: usize, token: Token) {
+        let id = self.open.pop().expect("A group to close");
+        let group = &mut self.tree.groups[id];
+        group.end = position + token.text.chars().count();
+        group.close = Some(token);
+    }
+
+    /// The tokens read, completed by the closers of the groups still open.
+    pub(crate) fn syntax(self, delimiters: &DelimiterSet) -> Syntax {
+        let completions = self
+            .open
+            .iter()
+            .rev()
+            .map(|&id| delimiters.closer(self.tree.groups[id].open.cat).clone())
+            .collect();
+        Syntax {
+            tokens: self.tokens,
+            completions,
+        }
+    }
+
+    /// The groups read, the ones still open ending at `len`.
+    pub(crate) fn tree(mut self, len: usize) -> Tree {
+        for id in self.open {
+            self.tree.groups[id].end = len;
+        }
+        self.tree
+    }
+}
+
+impl Syntax {
+    /// Parses the line like `parse_with`, keeping the groups of tokens.
+    pub fn parse_tree(input: &str, delimiters: &DelimiterSet) -> Result<Tree, ParseError> {
+        Ok(Matcher::read(input, delimiters)?.tree(input.chars().count()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree() {
+        let brackets = DelimiterSet::brackets();
+        let tree = Syntax::parse_tree("([]{<>})<(", &brackets).unwrap();
+        assert_eq!(tree.roots(), &[0, 4]);
+        assert_eq!(tree.group(0).children, vec![1, 2]);
+        assert_eq!(tree.group(2).span(), 3..7);
+        assert_eq!(tree.group(5).span(), 9..10);
+        assert_eq!(tree.group(5).close, None);
+        assert_eq!(tree.depth(), 3);
+
+        assert_eq!(tree.enclosing(4), Some(3));
+        assert_eq!(tree.enclosing(6), Some(2));
+        assert_eq!(tree.enclosing(9), Some(5));
+        assert_eq!(Syntax::parse_tree("", &brackets).unwrap().depth(), 0);
+
+        assert_eq!(tree.next_sibling(1), Some(2));
+        assert_eq!(tree.next_sibling(2), None);
+        assert_eq!(tree.previous_sibling(4), Some(0));
+        assert_eq!(tree.previous_sibling(0), None);
+
+        assert_eq!(
+            Syntax::parse_tree("(([]<)>", &brackets).unwrap_err(),
+            Syntax::parse("(([]<)>").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_matching() {
+        let set = DelimiterSet::new([("begin", "end"), ("(", ")")])
+            .unwrap()
+            .allow_text();
+        let line = "begin f(x) end (";
+        let tree = Syntax::parse_tree(line, &set).unwrap();
+        assert_eq!(tree.matching(2), Some(11));
+        assert_eq!(tree.matching(13), Some(0));
+        assert_eq!(tree.matching(7), Some(9));
+        assert_eq!(tree.matching(8), None);
+        assert_eq!(tree.matching(15), None);
+        assert_eq!(tree.group(0).close_at(), Some(11));
+    }
+}
@@ -0,0 +1,241 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use anyhow::Result;
+use aoc_common::{Answer, Grid, Solution};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::parse(input)
+    }
+
+    fn part1(m: &Self::Input) -> Result<Answer> {
+        let flashes: usize = m.iter().take(100).map(|(f, _)| f).sum();
+        Ok(flashes.into())
+    }
+
+    fn part2(m: &Self::Input) -> Result<Answer> {
+        let synced_at = m
+            .iter()
+            .enumerate()
+            .find(|&(_, (f, _))| f == m.grid.len())
+            .unwrap()
+            .0;
+        Ok((synced_at + 1).into())
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Map {
+    grid: Grid<u8>,
+}
+
+impl Map {
+    fn parse(input: &str) -> Result<Map> {
+        Ok(Self {
+            grid: Grid::parse_digits(input)?,
+        })
+    }
+
+    fn step(&self) -> (usize, Self) {
+        let mut next = Self {
+            grid: self.grid.map(|&c| c + 1),
+        };
+
+        let mut flashing = HashSet::new();
+        let mut flashing_count = 0;
+
+        loop {
+            for pos in next.grid.positions() {
+                if next.grid[pos] > 9 && !flashing.contains(&pos) {
+                    self.grid
+                        .neighbors8(pos)
+                        .for_each(|pos| next.grid[pos] += 1);
+                    flashing.insert(pos);
+                }
+            }
+            if flashing.len() == flashing_count {
+                break;
+            }
+            flashing_count = flashing.len();
+        }
+        for pos in next.grid.positions() {
+            if next.grid[pos] > 9 {
+                flashing.insert(pos);
+            }
+        }
+
+        next.grid.iter_mut().for_each(|c| {
+            if *c > 9 {
+                *c = 0
+            }
+        });
+
+        (flashing.len(), next)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (usize, Self)> {
+        std::iter::repeat(()).scan((0, self.clone()), |st, _| {
+            *st = st.1.step();
+            Some(st.clone())
+        })
+    }
+}
+
+impl Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn create_map() -> Result<Map> {
+        Map::parse(indoc! {"
+            5483143223
+            2745854711
+            5264556173
+            6141336146
+            6357385478
+            4167524645
+            2176841721
+            6882881134
+            4846848554
+            5283751526
+        "})
+    }
+
+    #[test]
+    fn test_parsing() -> Result<()> {
+        let m = create_map()?;
+
+        assert_eq!(m.grid[[0, 0]], 5);
+        assert_eq!(m.grid[[9, 9]], 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbours() -> Result<()> {
+        let m = create_map()?;
+        assert_eq!(
+            m.grid.neighbors8([0, 0]).collect::<Vec<_>>(),
+            vec![[0, 1], [1, 0], [1, 1]]
+        );
+        assert_eq!(
+            m.grid.neighbors8([1, 1]).collect::<Vec<_>>(),
+            vec![
+                [0, 0],
+                [0, 1],
+                [0, 2],
+                [1, 0],
+                [1, 2],
+                [2, 0],
+                [2, 1],
+                [2, 2]
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_step() -> Result<()> {
+        let m = Map::parse(indoc! {"
+            11111
+            19991
+            19191
+            19991
+            11111
+        "})?;
+
+        assert_eq!(
+            m.step(),
+            (
+                9,
+                Map::parse(indoc! {"
+                    34543
+                    40004
+                    50005
+                    40004
+                    34543
+                "})?
+            )
+        );
+
+        assert_eq!(
+            m.iter().nth(1).unwrap(),
+            (
+                0,
+                Map::parse(indoc! {"
+                    45654
+                    51115
+                    61116
+                    51115
+                    45654
+                "})?
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_flashes() -> Result<()> {
+        let m = Map::parse(indoc! {"
+            5483143223
+            2745854711
+            5264556173
+            6141336146
+            6357385478
+            4167524645
+            2176841721
+            6882881134
+            4846848554
+            5283751526
+        "})?;
+
+        let step_1 = Map::parse(indoc! {"
+            6594254334
+            3856965822
+            6375667284
+            7252447257
+            7468496589
+            5278635756
+            3287952832
+            7993992245
+            5957959665
+            6394862637
+        "})?;
+        assert_eq!(m.step(), (0, step_1));
+
+        let flashes: usize = m
+            .iter()
+            .take(10)
+            .map(|(f, m)| {
+                dbg!((f, m));
+                f
+            })
+            .sum();
+
+        assert_eq!(flashes, 204);
+
+        let flashes: usize = m.iter().take(100).map(|(f, _)| f).sum();
+        assert_eq!(flashes, 1656);
+
+        let synced_at = m
+            .iter()
+            .enumerate()
+            .find(|&(_, (f, _))| f == m.grid.len())
+            .unwrap()
+            .0;
+        assert_eq!(synced_at + 1, 195);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use anyhow::Result;
+use aoc_common::{Answer, Grid, ParseError, Solution};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Instructions;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Instructions::parse(input)
+    }
+
+    fn part1(ins: &Self::Input) -> Result<Answer> {
+        let map = Map::from_instructions(ins);
+        Ok(map.fold(ins.folds[0]).count().into())
+    }
+
+    fn part2(ins: &Self::Input) -> Result<Answer> {
+        let map = ins
+            .folds
+            .iter()
+            .fold(Map::from_instructions(ins), |m, &f| m.fold(f));
+        Ok(map.to_answer())
+    }
+}
+
+pub struct Instructions {
+    coordinates: HashSet<[usize; 2]>,
+    folds: Vec<[usize; 2]>,
+}
+
+impl Instructions {
+    fn new() -> Self {
+        Self {
+            coordinates: HashSet::new(),
+            folds: Vec::new(),
+        }
+    }
+
+    fn parse(desc: &str) -> Result<Self> {
+        peg::parser! {
+            grammar parser() for str {
+                pub(crate) rule instructions(c: &mut Instructions)
+                    = coordinates(c) ** "\n" "\n\n" folds(c) ** "\n"
+                rule coordinates(c: &mut Instructions)
+                    = x:num() "," y:num() { c.coordinates.insert([x, y]); }
+                rule num() -> usize
+                    = n:$(['0'..='9']+) { n.parse().unwrap() }
+                rule folds(c: &mut Instructions)
+                    = "fold along " a:axis() "=" p:num() { c.folds.push(if a == 'x' { [p, 0] } else { [0, p] }); }
+                rule axis() -> char
+                    = a:$(['x' | 'y']) { a.chars().next().unwrap() }
+
+            }
+        }
+
+        let mut instructions = Self::new();
+        let trimmed = desc.trim();
+        parser::instructions(trimmed, &mut instructions)
+            .map_err(|e| ParseError::from_peg(desc, trimmed, e))?;
+        Ok(instructions)
+    }
+
+    fn size(&self) -> [usize; 2] {
+        let x = *self.coordinates.iter().map(|[x, _]| x).max().unwrap();
+        let y = *self.coordinates.iter().map(|[_, y]| y).max().unwrap();
+        [x + 1, y + 1]
+    }
+}
+
+#[derive(PartialEq, Clone)]
+struct Map {
+    grid: Grid<bool>,
+}
+
+impl Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}x{}", self.grid.width(), self.grid.height())?;
+        write!(f, "{}", self.grid.map(|&c| if c { '#' } else { '.' }))
+    }
+}
+
+impl Map {
+    fn from_instructions(ins: &Instructions) -> Self {
+        let mut grid = Grid::from_elem(ins.size(), false);
+        for p in ins.coordinates.iter() {
+            grid[*p] = true;
+        }
+        Self { grid }
+    }
+
+    fn to_answer(&self) -> Answer {
+        Answer::Grid(self.grid.rows().map(<[bool]>::to_vec).collect())
+    }
+
+    fn count(&self) -> usize {
+        self.grid.iter().filter(|&v| *v).count()
+    }
+
+    fn fold(&self, axis: [usize; 2]) -> Self {
+        let mut rv;
+        match axis {
+            [x, 0] => {
+                rv = Grid::from_elem([x, self.grid.height()], false);
+                for [x, y] in self.grid.positions() {
+                    if x == rv.width() {
+                        continue;
+                    }
+                    let xn = if x <= rv.width() {
+                        x
+                    } else {
+                        rv.width() - (x - rv.width())
+                    };
+                    rv[[xn, y]] |= self.grid[[x, y]];
+                }
+            }
+            [0, y] => {
+                rv = Grid::from_elem([self.grid.width(), y], false);
+                for [x, y] in self.grid.positions() {
+                    if y == rv.height() {
+                        continue;
+                    }
+                    let yn = if y <= rv.height() {
+                        y
+                    } else {
+                        rv.height() - (y - rv.height())
+                    };
+                    rv[[x, yn]] |= self.grid[[x, y]];
+                }
+            }
+            _ => unreachable!(),
+        }
+        Self { grid: rv }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parsing() -> Result<()> {
+        let input = indoc! {"
+            6,10
+            0,14
+            9,10
+            0,3
+            10,4
+            4,11
+            6,0
+            6,12
+            4,1
+            0,13
+            10,12
+            3,4
+            3,0
+            8,4
+            1,10
+            2,14
+            8,10
+            9,0
+
+            fold along y=7
+            fold along x=5
+        "};
+
+        let ins = Instructions::parse(input).unwrap();
+        let map = Map::from_instructions(&ins);
+
+        assert_eq!(map.fold(ins.folds[0]).count(), 17);
+        Ok(())
+    }
+}
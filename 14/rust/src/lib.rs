@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use aoc_common::{Answer, ParseError, Solution};
+use itertools::Itertools;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Polymer;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(polymer: &Self::Input) -> Result<Answer> {
+        let mut polymer = polymer.clone();
+        polymer.mutate(10);
+        Ok(polymer.count_result().into())
+    }
+
+    fn part2(polymer: &Self::Input) -> Result<Answer> {
+        let mut polymer = polymer.clone();
+        polymer.mutate(40);
+        Ok(polymer.count_result().into())
+    }
+}
+
+fn parse_input(rules: &str) -> Result<Polymer> {
+    peg::parser! {
+        grammar parser() for str {
+            pub(crate) rule parse() -> Polymer
+                = t:template() "\n\n" r:rules() ** "\n" { Polymer::from(&t, HashMap::from_iter(r.into_iter())) }
+            rule template() -> String
+                = p:$(['A'..='Z']+) { p.into() }
+            rule rules() -> ([char; 2], char)
+                = p:pattern() " -> " e:element() { (p, e) }
+            rule pattern() -> [char; 2]
+                = a:element() b:element() { [a, b] }
+            rule element() -> char
+                = a:$(['A'..='Z']) { a.chars().next().unwrap() }
+
+        }
+    }
+    let trimmed = rules.trim();
+    Ok(parser::parse(trimmed).map_err(|e| ParseError::from_peg(rules, trimmed, e))?)
+}
+
+#[derive(Clone)]
+pub struct Polymer {
+    template: HashMap<[char; 2], usize>,
+    ends: [char; 2],
+    insertion_rules: HashMap<[char; 2], char>,
+}
+
+impl Polymer {
+    fn from(base: &str, insertion_rules: HashMap<[char; 2], char>) -> Self {
+        let mut template = HashMap::new();
+        let chars: Vec<char> = base.chars().collect();
+        for pair in chars.windows(2) {
+            *template.entry([pair[0], pair[1]]).or_insert(0) += 1;
+        }
+        let ends = [*chars.first().unwrap(), *chars.last().unwrap()];
+
+        Self {
+            template,
+            ends,
+            insertion_rules,
+        }
+    }
+    fn count_result(&self) -> usize {
+        let mut counts = HashMap::new();
+        for (k, v) in self
+            .template
+            .iter()
+            .flat_map(|(&[a, b], &v)| [(a, v), (b, v)])
+        {
+            *counts.entry(k).or_insert(0) += v;
+        }
+        *counts.get_mut(&self.ends[0]).unwrap() += 1;
+        *counts.get_mut(&self.ends[1]).unwrap() += 1;
+        let ((_, min), (_, max)) = counts
+            .iter()
+            .minmax_by_key(|(_, &v)| v)
+            .into_option()
+            .unwrap();
+        max / 2 - min / 2
+    }
+
+    fn apply_rules(&mut self) {
+        let mut template = HashMap::new();
+        for (pair, count) in &self.template {
+            if let Some(&new) = self.insertion_rules.get(pair) {
+                *template.entry([pair[0], new]).or_insert(0) += count;
+                *template.entry([new, pair[1]]).or_insert(0) += count;
+            } else {
+                *template.entry(*pair).or_insert(0) += count;
+            }
+        }
+        self.template = template;
+    }
+
+    fn mutate(&mut self, count: usize) {
+        for _ in 0..count {
+            self.apply_rules()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_polymer() -> Result<()> {
+        let input = indoc! {"
+            NNCB
+
+            CH -> B
+            HH -> N
+            CB -> H
+            NH -> C
+            HB -> C
+            HC -> B
+            HN -> C
+            NN -> C
+            BH -> H
+            NC -> B
+            NB -> B
+            BN -> B
+            BB -> N
+            BC -> B
+            CC -> N
+            CN -> C
+        "};
+
+        let mut polymer = parse_input(input)?;
+        assert_eq!(polymer.count_result(), 1);
+
+        polymer.mutate(10);
+        assert_eq!(polymer.count_result(), 1588);
+        polymer.mutate(30);
+        assert_eq!(polymer.count_result(), 2188189693529);
+
+        Ok(())
+    }
+
+    /// Inserts the elements into the literal string, step by step.
+    fn count_result_naive(template: &str, rules: &HashMap<[char; 2], char>, steps: usize) -> usize {
+        let mut polymer = template.chars().collect::<Vec<_>>();
+        for _ in 0..steps {
+            let mut next = vec![polymer[0]];
+            for pair in polymer.windows(2) {
+                if let Some(&new) = rules.get(&[pair[0], pair[1]]) {
+                    next.push(new);
+                }
+                next.push(pair[1]);
+            }
+            polymer = next;
+        }
+
+        let counts = polymer.iter().counts();
+        counts.values().max().unwrap() - counts.values().min().unwrap()
+    }
+
+    fn element() -> impl Strategy<Value = char> {
+        prop::sample::select(vec!['B', 'C', 'H', 'N'])
+    }
+
+    proptest! {
+        #[test]
+        fn test_polymer_matches_naive(
+            template in prop::collection::vec(element(), 2..8),
+            rules in prop::collection::hash_map([element(), element()], element(), 0..16),
+            steps in 0..8usize,
+        ) {
+            let template = template.into_iter().collect::<String>();
+            let mut polymer = Polymer::from(&template, rules.clone());
+            polymer.mutate(steps);
+
+            prop_assert_eq!(
+                polymer.count_result(),
+                count_result_naive(&template, &rules, steps)
+            );
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{cmp::Ordering, collections::BinaryHeap, fmt::Debug};
+
+use anyhow::Result;
+use aoc_common::{Answer, Grid, Solution};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Map::parse(input)
+    }
+
+    fn part1(map: &Self::Input) -> Result<Answer> {
+        let dst = map.find_shortest([0, 0], map.end());
+        Ok(dst.into())
+    }
+
+    fn part2(map: &Self::Input) -> Result<Answer> {
+        let map = scale_map(map);
+        let dst = map.find_shortest([0, 0], map.end());
+        Ok(dst.into())
+    }
+}
+
+fn scale_map(map: &Map) -> Map {
+    let [w, h] = map.grid.shape();
+    let mut grid = Grid::from_elem([w * 5, h * 5], 0);
+    for [x, y] in grid.positions() {
+        let f = x / w + y / h;
+        let dx = x % w;
+        let dy = y % h;
+
+        grid[[x, y]] = (map.grid[[dx, dy]] + f - 1) % 9 + 1;
+    }
+    Map { grid }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Map {
+    grid: Grid<usize>,
+}
+
+impl Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}x{}", self.grid.width(), self.grid.height())?;
+        write!(f, "{}", self.grid)
+    }
+}
+
+impl Map {
+    fn parse(input: &str) -> Result<Map> {
+        Ok(Self {
+            grid: Grid::parse_digits(input)?,
+        })
+    }
+
+    fn end(&self) -> [usize; 2] {
+        [self.grid.width() - 1, self.grid.height() - 1]
+    }
+
+    fn find_shortest(&self, start: [usize; 2], end: [usize; 2]) -> usize {
+        let mut dist = Grid::from_elem(self.grid.shape(), usize::MAX);
+        let mut heap = BinaryHeap::new();
+        dist[start] = 0;
+        heap.push(State {
+            cost: 0,
+            position: start,
+        });
+
+        while let Some(State { cost, position }) = heap.pop() {
+            if position == end {
+                return cost;
+            }
+            if cost > dist[position] {
+                continue;
+            }
+
+            for neigh in self.grid.neighbors4(position) {
+                let next = State {
+                    cost: cost + self.grid[neigh],
+                    position: neigh,
+                };
+
+                if next.cost < dist[next.position] {
+                    heap.push(next);
+                    dist[next.position] = next.cost;
+                }
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+// From https://doc.rust-lang.org/std/collections/binary_heap/index.html#examples
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct State {
+    cost: usize,
+    position: [usize; 2],
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .cmp(&self.cost)
+            .then_with(|| self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_path_finding() -> Result<()> {
+        let input = indoc! {"
+            1163751742
+            1381373672
+            2136511328
+            3694931569
+            7463417111
+            1319128137
+            1359912421
+            3125421639
+            1293138521
+            2311944581
+        "};
+
+        let map = Map::parse(input)?;
+        let dst = map.find_shortest([0, 0], map.end());
+        assert_eq!(dst, 40);
+
+        let map = scale_map(&map);
+        let dst = map.find_shortest([0, 0], map.end());
+        assert_eq!(dst, 315);
+
+        Ok(())
+    }
+
+    /// Relaxes every edge until the distances settle.
+    fn find_shortest_bellman_ford(map: &Map, start: [usize; 2], end: [usize; 2]) -> usize {
+        let mut dist = Grid::from_elem(map.grid.shape(), usize::MAX);
+        dist[start] = 0;
+        for _ in 0..map.grid.len() {
+            let mut changed = false;
+            for pos in map.grid.positions() {
+                if dist[pos] == usize::MAX {
+                    continue;
+                }
+                for neigh in map.grid.neighbors4(pos) {
+                    if dist[pos] + map.grid[neigh] < dist[neigh] {
+                        dist[neigh] = dist[pos] + map.grid[neigh];
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        dist[end]
+    }
+
+    fn map() -> impl Strategy<Value = Map> {
+        (1..8usize, 1..8usize).prop_flat_map(|(w, h)| {
+            prop::collection::vec(prop::collection::vec(1..=9usize, w), h).prop_map(|rows| Map {
+                grid: Grid::from_rows(rows).unwrap(),
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_path_finding_matches_bellman_ford(map in map()) {
+            prop_assert_eq!(
+                map.find_shortest([0, 0], map.end()),
+                find_shortest_bellman_ford(&map, [0, 0], map.end())
+            );
+        }
+    }
+}
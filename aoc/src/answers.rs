@@ -0,0 +1,130 @@
+use std::{fs::read_to_string, io::ErrorKind, path::Path};
+
+use anyhow::{Context, Result};
+use aoc_common::Answer;
+use serde::Deserialize;
+
+/// Answers recorded in `NN/answers.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Expected {
+    part1: Option<Recorded>,
+    part2: Option<Recorded>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Recorded {
+    Number(i64),
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Recorded {
+    fn lines(&self) -> Vec<String> {
+        match self {
+            Recorded::Number(n) => vec![n.to_string()],
+            Recorded::Text(t) => vec![t.clone()],
+            Recorded::Lines(l) => l.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    Missing,
+}
+
+impl Verdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail { .. } => "FAIL",
+            Verdict::Missing => "missing",
+        }
+    }
+}
+
+impl Expected {
+    /// Loads the answers file, treating a missing one as having no answers.
+    pub fn load(path: &Path) -> Result<Self> {
+        match read_to_string(path) {
+            Ok(contents) => Self::parse(&contents).with_context(|| format!("In {:?}", path)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read from {:?}", path)),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn verify(&self, answers: &[Answer; 2]) -> [Verdict; 2] {
+        [
+            verify(self.part1.as_ref(), &answers[0]),
+            verify(self.part2.as_ref(), &answers[1]),
+        ]
+    }
+}
+
+fn verify(expected: Option<&Recorded>, answer: &Answer) -> Verdict {
+    match expected {
+        None => Verdict::Missing,
+        Some(expected) => {
+            let expected = expected.lines();
+            let actual = answer.lines();
+            if expected == actual {
+                Verdict::Pass
+            } else {
+                Verdict::Fail { expected, actual }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        let expected = Expected::parse(
+            r##"
+            part1 = 42
+            part2 = ["#.", ".#"]
+            "##,
+        )?;
+
+        assert_eq!(
+            expected.verify(&[
+                Answer::Number(42),
+                Answer::Grid(vec![vec![true, false], vec![false, true]])
+            ]),
+            [Verdict::Pass, Verdict::Pass]
+        );
+        assert_eq!(
+            expected.verify(&[Answer::Number(41), Answer::Number(1)]),
+            [
+                Verdict::Fail {
+                    expected: vec!["42".into()],
+                    actual: vec!["41".into()]
+                },
+                Verdict::Fail {
+                    expected: vec!["#.".into(), ".#".into()],
+                    actual: vec!["1".into()]
+                }
+            ]
+        );
+
+        let expected = Expected::parse("part1 = \"42\"")?;
+        assert_eq!(
+            expected.verify(&[Answer::Number(42), Answer::Number(1)]),
+            [Verdict::Pass, Verdict::Missing]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use aoc_common::Timings;
+use serde::Serialize;
+
+/// Spread of the wall-clock times of one phase, in nanoseconds.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    pub fn of(times: &[Duration]) -> Self {
+        let mut nanos = times
+            .iter()
+            .map(|t| t.as_nanos() as u64)
+            .collect::<Vec<_>>();
+        nanos.sort_unstable();
+        Self {
+            min: nanos.first().copied().unwrap_or_default(),
+            median: nanos.get(nanos.len() / 2).copied().unwrap_or_default(),
+            max: nanos.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayReport {
+    pub fn new(day: u8, timings: &Timings) -> Self {
+        Self {
+            day,
+            parse: Stats::of(&timings.parse),
+            part1: Stats::of(&timings.part1),
+            part2: Stats::of(&timings.part2),
+        }
+    }
+}
+
+/// Results of a benchmark run, as saved to the JSON output file.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub repeat: usize,
+    pub days: Vec<DayReport>,
+}
+
+/// Formats a duration with a unit fitting its magnitude.
+pub fn format_nanos(nanos: u64) -> String {
+    match nanos {
+        n if n < 1_000 => format!("{}ns", n),
+        n if n < 1_000_000 => format!("{:.1}µs", n as f64 / 1e3),
+        n if n < 1_000_000_000 => format!("{:.1}ms", n as f64 / 1e6),
+        n => format!("{:.2}s", n as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let times = [5, 1, 3, 2, 4].map(Duration::from_nanos);
+        assert_eq!(
+            Stats::of(&times),
+            Stats {
+                min: 1,
+                median: 3,
+                max: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(999), "999ns");
+        assert_eq!(format_nanos(1_500), "1.5µs");
+        assert_eq!(format_nanos(12_340_000), "12.3ms");
+        assert_eq!(format_nanos(2_000_000_000), "2.00s");
+    }
+}
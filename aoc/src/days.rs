@@ -0,0 +1,117 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+use aoc_common::{bench, Solution, Solved};
+
+/// An implemented day, with its solution erased to a plain function.
+pub struct Day {
+    pub day: u8,
+    pub bench: fn(&str, usize) -> Result<Solved>,
+}
+
+impl Day {
+    fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            bench: bench::<S>,
+        }
+    }
+}
+
+pub fn all() -> Vec<Day> {
+    vec![
+        Day::of::<day01::Day01>(),
+        Day::of::<day02::Day02>(),
+        Day::of::<day10::Day10>(),
+        Day::of::<day11::Day11>(),
+        Day::of::<day12::Day12>(),
+        Day::of::<day13::Day13>(),
+        Day::of::<day14::Day14>(),
+        Day::of::<day15::Day15>(),
+        Day::of::<day16::Day16>(),
+    ]
+}
+
+/// Days selected on the command line: `all`, `14`, `10..16` or `10..=16`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selection {
+    All,
+    Single(u8),
+    Range(RangeInclusive<u8>),
+}
+
+impl Selection {
+    /// Picks the selected days, skipping the unimplemented ones of a range.
+    pub fn select(&self, days: Vec<Day>) -> Result<Vec<Day>> {
+        match self {
+            Selection::All => Ok(days),
+            Selection::Single(d) => {
+                let selected = days
+                    .into_iter()
+                    .filter(|day| day.day == *d)
+                    .collect::<Vec<_>>();
+                if selected.is_empty() {
+                    bail!("Day {} is not implemented", d);
+                }
+                Ok(selected)
+            }
+            Selection::Range(r) => Ok(days
+                .into_iter()
+                .filter(|day| r.contains(&day.day))
+                .collect()),
+        }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let day = |d: &str| d.parse::<u8>().map_err(|_| anyhow!("Invalid day {:?}", d));
+
+        if s == "all" {
+            Ok(Selection::All)
+        } else if let Some((start, end)) = s.split_once("..=") {
+            Ok(Selection::Range(day(start)?..=day(end)?))
+        } else if let Some((start, end)) = s.split_once("..") {
+            let end = day(end)?
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("Empty range {:?}", s))?;
+            Ok(Selection::Range(day(start)?..=end))
+        } else {
+            Ok(Selection::Single(day(s)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selection() -> Result<()> {
+        assert_eq!("all".parse::<Selection>()?, Selection::All);
+        assert_eq!("14".parse::<Selection>()?, Selection::Single(14));
+        assert_eq!("10..=16".parse::<Selection>()?, Selection::Range(10..=16));
+        assert_eq!("10..16".parse::<Selection>()?, Selection::Range(10..=15));
+        assert!("x".parse::<Selection>().is_err());
+        assert!("1..x".parse::<Selection>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_select() -> Result<()> {
+        let days = |s: &str| -> Result<Vec<u8>> {
+            Ok(s.parse::<Selection>()?
+                .select(all())?
+                .iter()
+                .map(|d| d.day)
+                .collect())
+        };
+        assert_eq!(days("all")?.len(), 9);
+        assert_eq!(days("14")?, vec![14]);
+        assert_eq!(days("1..=11")?, vec![1, 2, 10, 11]);
+        assert!(days("3").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use aoc_common::read_input;
+
+/// Source of puzzle inputs missing from the cache.
+pub trait Fetcher {
+    fn fetch(&self, day: u8) -> Result<String>;
+}
+
+/// Fetches inputs from `{endpoint}/{year}/day/{day}/input`, authenticated
+/// by the session cookie of the site.
+pub struct HttpFetcher {
+    pub endpoint: String,
+    pub year: u16,
+    pub session: String,
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, day: u8) -> Result<String> {
+        let url = format!(
+            "{}/{}/day/{}/input",
+            self.endpoint.trim_end_matches('/'),
+            self.year,
+            day
+        );
+        let input = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/vlaci/AoC2021")
+            .call()
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .into_string()?;
+        Ok(input)
+    }
+}
+
+/// Puzzle inputs kept as `NN/input` under the cache directory, fetched
+/// on first use when a fetcher is available.
+pub struct Inputs {
+    cache: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl Inputs {
+    pub fn new(cache: &Path, fetcher: Option<Box<dyn Fetcher>>) -> Self {
+        Self {
+            cache: cache.into(),
+            fetcher,
+        }
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.cache.join(format!("{:02}", day)).join("input")
+    }
+
+    pub fn get(&self, day: u8) -> Result<String> {
+        let path = self.path(day);
+        if path.exists() {
+            return read_input(&path.to_string_lossy());
+        }
+
+        let fetcher = self.fetcher.as_ref().ok_or_else(|| {
+            anyhow!(
+                "No input at {:?} and no session token to fetch it with",
+                path
+            )
+        })?;
+        let input = fetcher.fetch(day)?;
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        write(&path, &input).with_context(|| format!("Failed to write to {:?}", path))?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs::remove_dir_all, process};
+
+    use super::*;
+    use crate::stand_in::StandIn;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-{}-{}", name, process::id()));
+        let _ = remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_and_cache() -> Result<()> {
+        let server = StandIn::start("secret", &[(1, "199\n200\n")])?;
+        let cache = cache_dir("fetch");
+        let inputs = Inputs::new(
+            &cache,
+            Some(Box::new(HttpFetcher {
+                endpoint: server.endpoint(),
+                year: 2021,
+                session: "secret".into(),
+            })),
+        );
+
+        assert_eq!(inputs.get(1)?, "199\n200\n");
+        assert_eq!(
+            read_input(&cache.join("01/input").to_string_lossy())?,
+            "199\n200\n"
+        );
+        assert!(inputs.get(2).is_err());
+
+        drop(server);
+        assert_eq!(inputs.get(1)?, "199\n200\n");
+
+        remove_dir_all(&cache)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let server = StandIn::start("secret", &[(1, "199\n200\n")])?;
+        let fetcher = HttpFetcher {
+            endpoint: server.endpoint(),
+            year: 2021,
+            session: "wrong".into(),
+        };
+
+        assert!(fetcher.fetch(1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_offline() {
+        let cache = cache_dir("offline");
+        let inputs = Inputs::new(&cache, None);
+
+        assert!(inputs.get(1).is_err());
+    }
+}
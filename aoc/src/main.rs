@@ -0,0 +1,256 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use aoc_common::{print_json, with_file, Format, Solved};
+use clap::{Parser, Subcommand};
+
+mod answers;
+mod bench;
+mod days;
+mod inputs;
+#[cfg(test)]
+mod stand_in;
+
+use answers::{Expected, Verdict};
+use bench::{format_nanos, DayReport, Report, Stats};
+use days::{Day, Selection};
+use inputs::{Fetcher, HttpFetcher, Inputs};
+
+#[derive(Parser)]
+#[clap(name = "aoc", about = "Runs the Advent of Code solutions")]
+struct Opts {
+    /// Directory containing the `NN/input` files, also caching fetched ones
+    #[clap(long, default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/.."))]
+    root: PathBuf,
+
+    /// Site to fetch missing inputs from
+    #[clap(long, default_value = "https://adventofcode.com")]
+    endpoint: String,
+
+    /// Year of the puzzles to fetch
+    #[clap(long, default_value = "2021")]
+    year: u16,
+
+    /// Session cookie of the site, needed to fetch missing inputs
+    #[clap(long, env = "AOC_SESSION", hide_env_values = true)]
+    session: Option<String>,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the selected days and prints their answers
+    Run {
+        /// `all`, a single day like `14` or a range like `10..=16`
+        days: Selection,
+
+        /// Output format of the answers
+        #[clap(long, value_enum, default_value = "text")]
+        format: Format,
+    },
+    /// Checks the answers of the selected days against `NN/answers.toml`
+    Verify {
+        /// `all`, a single day like `14` or a range like `10..=16`
+        #[clap(default_value = "all")]
+        days: Selection,
+    },
+    /// Repeats each phase of the selected days and reports their timings
+    Bench {
+        /// `all`, a single day like `14` or a range like `10..=16`
+        #[clap(default_value = "all")]
+        days: Selection,
+
+        /// Number of repetitions
+        #[clap(short = 'n', long, default_value = "10")]
+        repeat: usize,
+
+        /// Saves the results as JSON, so that runs can be diffed
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let fetcher = opts.session.as_ref().map(|session| {
+        Box::new(HttpFetcher {
+            endpoint: opts.endpoint.clone(),
+            year: opts.year,
+            session: session.clone(),
+        }) as Box<dyn Fetcher>
+    });
+    let inputs = Inputs::new(&opts.root, fetcher);
+
+    match opts.command {
+        Command::Run { days, format } => run(&inputs, days.select(days::all())?, format),
+        Command::Verify { days } => verify(&opts.root, &inputs, days.select(days::all())?),
+        Command::Bench {
+            days,
+            repeat,
+            output,
+        } => bench(&inputs, days.select(days::all())?, repeat, output),
+    }
+}
+
+fn day_dir(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("{:02}", day))
+}
+
+fn solve(inputs: &Inputs, day: &Day, repeat: usize) -> Result<Solved> {
+    let input = inputs.get(day.day)?;
+    (day.bench)(&input, repeat).map_err(|e| with_file(e, &inputs.path(day.day).to_string_lossy()))
+}
+
+fn run(inputs: &Inputs, days: Vec<Day>, format: Format) -> Result<()> {
+    let mut rows = vec![header(&[
+        "Day", "Part 1", "Part 2", "Parse", "Time 1", "Time 2",
+    ])];
+    let mut failed = 0;
+
+    for day in days {
+        match solve(inputs, &day, 1) {
+            Ok(solved) if format == Format::Json => print_json(day.day, None, &solved)?,
+            Ok(Solved {
+                answers: [fst, snd],
+                timings,
+            }) => {
+                let time = |t: &[_]| vec![format_nanos(Stats::of(t).median)];
+                rows.push(vec![
+                    vec![format!("{:02}", day.day)],
+                    fst.lines(),
+                    snd.lines(),
+                    time(&timings.parse),
+                    time(&timings.part1),
+                    time(&timings.part2),
+                ]);
+            }
+            Err(e) => {
+                eprintln!("Day {:02} failed: {:#}", day.day, e);
+                failed += 1;
+                rows.push(vec![vec![format!("{:02}", day.day)], vec!["-".to_string()]]);
+            }
+        }
+    }
+
+    if format == Format::Text {
+        print_table(&rows);
+    }
+
+    if failed > 0 {
+        bail!("{} day(s) failed", failed);
+    }
+    Ok(())
+}
+
+fn verify(root: &Path, inputs: &Inputs, days: Vec<Day>) -> Result<()> {
+    let mut rows = vec![header(&["Day", "Part 1", "Part 2"])];
+    let mut failed = 0;
+
+    for day in days {
+        let verdicts = Expected::load(&day_dir(root, day.day).join("answers.toml"))
+            .and_then(|expected| Ok(expected.verify(&solve(inputs, &day, 1)?.answers)));
+        let [fst, snd] = match verdicts {
+            Ok(verdicts) => {
+                for (part, verdict) in verdicts.iter().enumerate() {
+                    if let Verdict::Fail { expected, actual } = verdict {
+                        eprintln!(
+                            "Day {:02} part {}: expected {}, got {}",
+                            day.day,
+                            part + 1,
+                            expected.join("\n"),
+                            actual.join("\n")
+                        );
+                        failed += 1;
+                    }
+                }
+                verdicts.map(|v| v.label().to_string())
+            }
+            Err(e) => {
+                eprintln!("Day {:02} failed: {:#}", day.day, e);
+                failed += 1;
+                ["error".to_string(), "error".to_string()]
+            }
+        };
+        rows.push(vec![vec![format!("{:02}", day.day)], vec![fst], vec![snd]]);
+    }
+
+    print_table(&rows);
+
+    if failed > 0 {
+        bail!("{} answer(s) failed verification", failed);
+    }
+    Ok(())
+}
+
+fn bench(inputs: &Inputs, days: Vec<Day>, repeat: usize, output: Option<PathBuf>) -> Result<()> {
+    if repeat == 0 {
+        bail!("The number of repetitions must be positive");
+    }
+
+    let mut rows = vec![header(&["Day", "Phase", "Min", "Median", "Max"])];
+    let mut report = Report {
+        repeat,
+        days: Vec::new(),
+    };
+
+    for day in days {
+        let Solved { timings, .. } =
+            solve(inputs, &day, repeat).with_context(|| format!("Day {:02} failed", day.day))?;
+        let day = DayReport::new(day.day, &timings);
+        for (phase, stats) in [
+            ("parse", &day.parse),
+            ("part 1", &day.part1),
+            ("part 2", &day.part2),
+        ] {
+            rows.push(vec![
+                vec![format!("{:02}", day.day)],
+                vec![phase.to_string()],
+                vec![format_nanos(stats.min)],
+                vec![format_nanos(stats.median)],
+                vec![format_nanos(stats.max)],
+            ]);
+        }
+        report.days.push(day);
+    }
+
+    print_table(&rows);
+
+    if let Some(path) = output {
+        let file = File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+        serde_json::to_writer_pretty(file, &report)?;
+    }
+    Ok(())
+}
+
+fn header(columns: &[&str]) -> Vec<Vec<String>> {
+    columns.iter().map(|c| vec![c.to_string()]).collect()
+}
+
+/// Prints rows of cells, where a cell may span several lines.
+fn print_table(rows: &[Vec<Vec<String>>]) {
+    let mut widths = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = cell.iter().map(|l| l.chars().count()).fold(*w, usize::max);
+        }
+    }
+
+    for row in rows {
+        let height = row.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..height {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &w)| format!("{:w$}", cell.get(i).map_or("", |l| l), w = w))
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("{}", line.trim_end());
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Stand-in for the puzzle input endpoint, so fetching can be tested
+//! offline.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use anyhow::Result;
+
+/// Serves `/{year}/day/{day}/input` on localhost to requests carrying the
+/// expected session cookie, until dropped.
+pub struct StandIn {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl StandIn {
+    pub fn start(session: &str, inputs: &[(u8, &str)]) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let cookie = format!("session={}", session);
+        let inputs = inputs
+            .iter()
+            .map(|&(day, input)| (format!("/2021/day/{}/input", day), input.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = respond(stream, &cookie, &inputs);
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for StandIn {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake up the listener blocked in accept
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn respond(stream: TcpStream, cookie: &str, inputs: &HashMap<String, String>) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+    let mut authorized = false;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("cookie") && value.trim() == cookie {
+                authorized = true;
+            }
+        }
+    }
+
+    let (status, body) = match inputs.get(path) {
+        _ if !authorized => ("400 Bad Request", "Puzzle inputs differ by user."),
+        Some(input) => ("200 OK", input.as_str()),
+        None => ("404 Not Found", "Not found"),
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use std::{error::Error, fmt::Display};
+
+use peg::{error, str::LineCol};
+
+/// Labels explaining the offending text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels {
+    /// Shown after the carets
+    pub primary: Option<String>,
+    pub secondary: Option<Secondary>,
+}
+
+/// Another part of the offending line explaining the error, like where a
+/// group it closes was opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Secondary {
+    /// Column in characters, starting from 1
+    pub column: usize,
+    /// Width in characters
+    pub width: usize,
+    pub label: String,
+}
+
+/// Malformed puzzle input, pointing at the offending text.
+///
+/// Its `Display` renders a snippet of the offending line with the text
+/// underlined by carets, and the secondary span by dashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Name of the input file, if known
+    pub file: Option<String>,
+    /// Line number, starting from 1
+    pub line: usize,
+    /// Column in characters, starting from 1
+    pub column: usize,
+    /// The offending text
+    pub text: String,
+    /// The whole line containing the offending text
+    pub source_line: String,
+    /// Boxed, as most errors have none
+    pub labels: Option<Box<Labels>>,
+}
+
+impl ParseError {
+    /// Points at `len` bytes from `offset` of `source`. An empty span marks
+    /// the single position, like the end of input.
+    pub fn at(source: &str, offset: usize, len: usize, message: impl Into<String>) -> Self {
+        let offset = floor_char_boundary(source, offset);
+        let end = floor_char_boundary(source, offset + len);
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+
+        Self {
+            message: message.into(),
+            file: None,
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            text: source[offset..end.min(line_end)].to_string(),
+            source_line: source[line_start..line_end].to_string(),
+            labels: None,
+        }
+    }
+
+    /// Points at `part`, a slice of `source`.
+    pub fn at_slice(source: &str, part: &str, message: impl Into<String>) -> Self {
+        Self::at(source, offset_in(source, part), part.len(), message)
+    }
+
+    /// Converts the error of a peg parser run on `parsed`, a slice of
+    /// `source`, underlining the rest of the word it stopped at.
+    pub fn from_peg(source: &str, parsed: &str, err: error::ParseError<LineCol>) -> Self {
+        let offset = offset_in(source, parsed) + err.location.offset;
+        let rest = &source[offset..];
+        let len = match rest.chars().next() {
+            Some(c) if c.is_whitespace() => c.len_utf8(),
+            _ => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+        };
+        Self::at(source, offset, len, format!("Expected {}", err.expected))
+    }
+
+    pub fn with_file(self, file: &str) -> Self {
+        Self {
+            file: Some(file.into()),
+            ..self
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.labels.get_or_insert_with(Default::default).primary = Some(label.into());
+        self
+    }
+
+    /// Marks `width` characters from `column` of the same line too.
+    pub fn with_secondary(mut self, column: usize, width: usize, label: impl Into<String>) -> Self {
+        self.labels.get_or_insert_with(Default::default).secondary = Some(Secondary {
+            column,
+            width,
+            label: label.into(),
+        });
+        self
+    }
+}
+
+/// Attaches the file name to the error, if it is a `ParseError`.
+pub fn with_file(err: anyhow::Error, file: &str) -> anyhow::Error {
+    match err.downcast::<ParseError>() {
+        Ok(e) => e.with_file(file).into(),
+        Err(e) => e,
+    }
+}
+
+/// Byte offset of `part` within `source`, which it must be a slice of.
+fn offset_in(source: &str, part: &str) -> usize {
+    let offset = part.as_ptr() as usize - source.as_ptr() as usize;
+    assert!(offset + part.len() <= source.len(), "Not a slice of source");
+    offset
+}
+
+fn floor_char_boundary(s: &str, mut i: usize) -> usize {
+    i = i.min(s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = self.line.to_string().len();
+        writeln!(f, "{}", self.message)?;
+        writeln!(
+            f,
+            "{:gutter$}--> {}:{}:{}",
+            "",
+            self.file.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.column,
+            gutter = gutter
+        )?;
+        writeln!(f, "{:gutter$} |", "", gutter = gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+
+        let labels = self.labels.as_deref();
+        let primary = labels.and_then(|l| l.primary.as_ref());
+        let secondary = labels.and_then(|l| l.secondary.as_ref());
+        let mut marks = Vec::new();
+        let mut mark = |column: usize, width: usize, c: char| {
+            let end = column - 1 + width;
+            if marks.len() < end {
+                marks.resize(end, ' ');
+            }
+            marks[column - 1..end].fill(c);
+        };
+        if let Some(secondary) = secondary {
+            mark(secondary.column, secondary.width, '-');
+        }
+        mark(self.column, self.text.chars().count().max(1), '^');
+        let marks = marks.into_iter().collect::<String>();
+        match primary {
+            Some(label) => write!(f, "{:gutter$} | {} {}", "", marks, label, gutter = gutter)?,
+            None => write!(f, "{:gutter$} | {}", "", marks, gutter = gutter)?,
+        }
+
+        if let Some(secondary) = secondary {
+            let indent = secondary.column - 1;
+            write!(
+                f,
+                "\n{:gutter$} | {:indent$}|\n{:gutter$} | {:indent$}{}",
+                "",
+                "",
+                "",
+                "",
+                secondary.label,
+                gutter = gutter,
+                indent = indent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let source = "123\n45x6\n";
+        let e = ParseError::at(source, 6, 1, "Invalid digit");
+        assert_eq!(e.line, 2);
+        assert_eq!(e.column, 3);
+        assert_eq!(e.text, "x");
+        assert_eq!(e.source_line, "45x6");
+
+        let e = ParseError::at_slice(source, &source[4..8], "Bad row");
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.text, "45x6");
+
+        let e = ParseError::at(source, source.len(), 0, "Unexpected end");
+        assert_eq!((e.line, e.column), (3, 1));
+        assert_eq!(e.text, "");
+    }
+
+    #[test]
+    fn test_render() {
+        let e = ParseError::at("123\n45x6\n", 6, 1, "Invalid digit 'x'").with_file("11/input");
+        assert_eq!(
+            e.to_string(),
+            "Invalid digit 'x'\n --> 11/input:2:3\n  |\n2 | 45x6\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_render_secondary() {
+        let e = ParseError::at("ab\n(([]<)>", 8, 1, "Mismatched")
+            .with_label("mismatched closer")
+            .with_secondary(5, 1, "opened here");
+        assert_eq!(
+            e.to_string(),
+            "Mismatched\n --> <input>:2:6\n  |\n2 | (([]<)>\n  |     -^ mismatched closer\n  |     |\n  |     opened here"
+        );
+    }
+
+    #[test]
+    fn test_from_peg() {
+        peg::parser! {
+            grammar parser() for str {
+                pub(crate) rule numbers() = (['0'..='9']+) ** "\n"
+            }
+        }
+
+        let source = "\n12\n3x4 5\n";
+        let parsed = source.trim();
+        let e = ParseError::from_peg(source, parsed, parser::numbers(parsed).unwrap_err());
+        assert_eq!((e.line, e.column), (3, 2));
+        assert_eq!(e.text, "x4");
+        assert_eq!(e.message, r#"Expected one of "\n", EOF, ['0'..='9']"#);
+    }
+
+    #[test]
+    fn test_with_file() {
+        let e = with_file(ParseError::at("x", 0, 1, "Bad").into(), "input");
+        assert_eq!(
+            e.downcast::<ParseError>().unwrap().file.as_deref(),
+            Some("input")
+        );
+    }
+}
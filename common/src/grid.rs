@@ -0,0 +1,303 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    slice,
+};
+
+use anyhow::{bail, Result};
+
+use crate::ParseError;
+
+/// A dense 2D grid stored row by row, indexed by `[x, y]` where `x` is the
+/// column and `y` is the row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    shape: [usize; 2],
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn from_elem(shape: [usize; 2], elem: T) -> Self {
+        Self {
+            cells: vec![elem; shape[0] * shape[1]],
+            shape,
+        }
+    }
+}
+
+impl<T: From<u8>> Grid<T> {
+    /// Parses rows of single digits, like `1163751742`.
+    pub fn parse_digits(input: &str) -> Result<Self, ParseError> {
+        let rows = input.trim().split('\n').collect::<Vec<_>>();
+        let width = rows[0].len();
+        if width == 0 {
+            return Err(ParseError::at(input, 0, 0, "Empty file"));
+        }
+
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in rows.iter() {
+            if row.len() != width {
+                return Err(ParseError::at_slice(
+                    input,
+                    row,
+                    format!("Row has length {}, expected {}", row.len(), width),
+                ));
+            }
+            for (i, c) in row.char_indices() {
+                let digit = c.to_digit(10).ok_or_else(|| {
+                    ParseError::at_slice(
+                        input,
+                        &row[i..i + c.len_utf8()],
+                        format!("Invalid digit {:?}", c),
+                    )
+                })?;
+                cells.push(T::from(digit as u8));
+            }
+        }
+
+        Ok(Self {
+            cells,
+            shape: [width, rows.len()],
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        if rows.iter().any(|r| r.len() != width) {
+            bail!("Rows are of different length");
+        }
+        Ok(Self {
+            cells: rows.into_iter().flatten().collect(),
+            shape: [width, height],
+        })
+    }
+
+    /// `[width, height]` of the grid.
+    pub fn shape(&self) -> [usize; 2] {
+        self.shape
+    }
+
+    pub fn width(&self) -> usize {
+        self.shape[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.shape[1]
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: [usize; 2]) -> bool {
+        pos[0] < self.shape[0] && pos[1] < self.shape[1]
+    }
+
+    pub fn get(&self, pos: [usize; 2]) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self[pos])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: [usize; 2]) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self[pos])
+        } else {
+            None
+        }
+    }
+
+    /// Values in row-major order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = [usize; 2]> {
+        let [width, height] = self.shape;
+        (0..height).flat_map(move |y| (0..width).map(move |x| [x, y]))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[self.shape[0] * y..self.shape[0] * (y + 1)]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero chunk size
+        self.cells.chunks(self.shape[0].max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.shape[0].max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.shape[0]).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            shape: self.shape,
+        }
+    }
+
+    /// The up to 4 orthogonally adjacent positions.
+    pub fn neighbors4(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        [[-1, 0], [1, 0], [0, -1], [0, 1]]
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    /// The up to 8 adjacent positions, diagonals included.
+    pub fn neighbors8(&self, pos: [usize; 2]) -> impl Iterator<Item = [usize; 2]> + '_ {
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| [dx, dy]))
+            .filter(|&d| d != [0, 0])
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    fn offset(&self, pos: [usize; 2], d: [isize; 2]) -> Option<[usize; 2]> {
+        let x = pos[0].checked_add_signed(d[0])?;
+        let y = pos[1].checked_add_signed(d[1])?;
+        Some([x, y]).filter(|&p| self.contains(p))
+    }
+}
+
+impl<T> Index<[usize; 2]> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: [usize; 2]) -> &Self::Output {
+        assert!(
+            self.contains(index),
+            "{:?} is out of {:?}",
+            index,
+            self.shape
+        );
+        &self.cells[self.shape[0] * index[1] + index[0]]
+    }
+}
+
+impl<T> IndexMut<[usize; 2]> for Grid<T> {
+    fn index_mut(&mut self, index: [usize; 2]) -> &mut Self::Output {
+        assert!(
+            self.contains(index),
+            "{:?} is out of {:?}",
+            index,
+            self.shape
+        );
+        &mut self.cells[self.shape[0] * index[1] + index[0]]
+    }
+}
+
+/// Renders the grid row by row, with cells next to each other.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for c in row {
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_grid() -> Result<Grid<u8>> {
+        Ok(Grid::parse_digits("123\n456\n")?)
+    }
+
+    #[test]
+    fn test_parse_digits() -> Result<()> {
+        let g = create_grid()?;
+        assert_eq!(g.shape(), [3, 2]);
+        assert_eq!(g[[0, 0]], 1);
+        assert_eq!(g[[2, 0]], 3);
+        assert_eq!(g[[0, 1]], 4);
+        assert_eq!(g.to_string(), "123\n456\n");
+
+        assert!(Grid::<u8>::parse_digits("").is_err());
+        let e = Grid::<u8>::parse_digits("12\n3x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+        let e = Grid::<u8>::parse_digits("12\n345").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "345"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_access() -> Result<()> {
+        let mut g = create_grid()?;
+        assert_eq!(g.get([2, 1]), Some(&6));
+        assert_eq!(g.get([3, 0]), None);
+        assert_eq!(g.get([0, 2]), None);
+
+        *g.get_mut([1, 1]).unwrap() = 0;
+        g[[0, 0]] += 1;
+        assert_eq!(
+            g.iter().copied().collect::<Vec<_>>(),
+            vec![2, 2, 3, 4, 0, 6]
+        );
+        assert_eq!(
+            g.map(|&c| c > 2),
+            Grid::from_rows(vec![vec![false, false, true], vec![true, false, true]])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_views() -> Result<()> {
+        let g = create_grid()?;
+        assert_eq!(
+            g.positions().collect::<Vec<_>>(),
+            vec![[0, 0], [1, 0], [2, 0], [0, 1], [1, 1], [2, 1]]
+        );
+        assert_eq!(g.row(1), &[4, 5, 6]);
+        assert_eq!(g.rows().collect::<Vec<_>>(), vec![&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!(g.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(
+            g.columns()
+                .map(|c| c.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_neighbors() -> Result<()> {
+        let g = Grid::from_elem([3, 3], 0);
+        assert_eq!(
+            g.neighbors4([0, 0]).collect::<Vec<_>>(),
+            vec![[1, 0], [0, 1]]
+        );
+        assert_eq!(
+            g.neighbors4([1, 1]).collect::<Vec<_>>(),
+            vec![[0, 1], [2, 1], [1, 0], [1, 2]]
+        );
+        assert_eq!(
+            g.neighbors8([0, 0]).collect::<Vec<_>>(),
+            vec![[0, 1], [1, 0], [1, 1]]
+        );
+        assert_eq!(g.neighbors8([1, 1]).count(), 8);
+        assert_eq!(g.neighbors8([2, 2]).count(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    fmt::Display,
+    fs::read_to_string,
+    io::{stdin, Read},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use serde::{Serialize, Serializer};
+
+mod error;
+mod grid;
+
+pub use error::{with_file, Labels, ParseError, Secondary};
+pub use grid::Grid;
+
+/// A puzzle solution for a single day.
+///
+/// The input is parsed once and both parts are computed from the parsed
+/// representation, so tooling can drive every day the same way.
+pub trait Solution {
+    /// Day of the puzzle, as in `NN/` of the repository layout.
+    const DAY: u8;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    /// Dots to be read as letters, stored row by row.
+    Grid(Vec<Vec<bool>>),
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl Answer {
+    /// Renders the answer as lines of text, a single one for numbers.
+    pub fn lines(&self) -> Vec<String> {
+        match self {
+            Answer::Number(n) => vec![n.to_string()],
+            Answer::Grid(rows) => rows
+                .iter()
+                .map(|row| row.iter().map(|&c| if c { '#' } else { '.' }).collect())
+                .collect(),
+        }
+    }
+}
+
+/// Numbers are serialized as such, grids as a list of rows.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Answer::Number(n) => serializer.serialize_i128(*n),
+            Answer::Grid(_) => self.lines().serialize(serializer),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Grid(_) => {
+                for line in self.lines() {
+                    write!(f, "\n{}", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// Command line of the binaries of every day.
+#[derive(Parser)]
+pub struct Args {
+    /// Output format of the answers
+    #[clap(long, value_enum, default_value = "text")]
+    pub format: Format,
+
+    /// Puzzle inputs answered in turn, `-` reads from stdin
+    #[clap(required = true)]
+    pub inputs: Vec<String>,
+}
+
+/// Reads the puzzle input from a file, or from stdin if `path` is `-`.
+pub fn read_input(path: &str) -> Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read from stdin")?;
+        return Ok(input);
+    }
+    read_to_string(path).with_context(|| format!("Failed to read from {:?}", path))
+}
+
+/// Parses the input and computes the answers to both parts.
+pub fn solve<S: Solution>(input: &str) -> Result<[Answer; 2]> {
+    let parsed = S::parse(input)?;
+    Ok([S::part1(&parsed)?, S::part2(&parsed)?])
+}
+
+/// Wall-clock time of each phase, one entry per repetition.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub parse: Vec<Duration>,
+    pub part1: Vec<Duration>,
+    pub part2: Vec<Duration>,
+}
+
+/// Answers along with the time it took to compute them.
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub answers: [Answer; 2],
+    pub timings: Timings,
+}
+
+fn timed<T>(times: &mut Vec<Duration>, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let start = Instant::now();
+    let rv = f()?;
+    times.push(start.elapsed());
+    Ok(rv)
+}
+
+/// Solves the input `repeat` times, timing parsing and each part separately.
+pub fn bench<S: Solution>(input: &str, repeat: usize) -> Result<Solved> {
+    let mut timings = Timings::default();
+    let mut answers = solve_timed::<S>(input, &mut timings)?;
+    for _ in 1..repeat {
+        answers = solve_timed::<S>(input, &mut timings)?;
+    }
+    Ok(Solved { answers, timings })
+}
+
+fn solve_timed<S: Solution>(input: &str, timings: &mut Timings) -> Result<[Answer; 2]> {
+    let parsed = timed(&mut timings.parse, || S::parse(input))?;
+    Ok([
+        timed(&mut timings.part1, || S::part1(&parsed))?,
+        timed(&mut timings.part2, || S::part2(&parsed))?,
+    ])
+}
+
+/// Machine-readable answer to one part, `elapsed` is in nanoseconds.
+///
+/// Binaries answering something else than the parts of the puzzle name
+/// what they answered by `controller` instead.
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    pub day: u8,
+    /// Set when answering several inputs in one run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controller: Option<&'a str>,
+    pub answer: &'a Answer,
+    pub elapsed: u64,
+}
+
+impl Solved {
+    /// Records of both parts, timed by the median of their repetitions.
+    pub fn records(&self, day: u8) -> [Record<'_>; 2] {
+        let median = |times: &[Duration]| {
+            let mut times = times.to_vec();
+            times.sort_unstable();
+            times
+                .get(times.len() / 2)
+                .map_or(0, |t| t.as_nanos() as u64)
+        };
+        [
+            Record {
+                day,
+                input: None,
+                part: Some(1),
+                controller: None,
+                answer: &self.answers[0],
+                elapsed: median(&self.timings.part1),
+            },
+            Record {
+                day,
+                input: None,
+                part: Some(2),
+                controller: None,
+                answer: &self.answers[1],
+                elapsed: median(&self.timings.part2),
+            },
+        ]
+    }
+}
+
+/// Prints the records of both parts as JSON, one per line.
+pub fn print_json(day: u8, input: Option<&str>, solved: &Solved) -> Result<()> {
+    for record in solved.records(day) {
+        let record = Record { input, ..record };
+        println!("{}", serde_json::to_string(&record)?);
+    }
+    Ok(())
+}
+
+pub fn print_answers(answers: &[Answer; 2]) {
+    println!("The answer to the first part is {}", answers[0]);
+    println!("The answer to the second part is {}", answers[1]);
+}
+
+/// Entry point shared by the binaries of every day.
+pub fn run<S: Solution>() -> Result<()> {
+    run_with::<S>(Args::parse())
+}
+
+/// Answers the inputs given on the command line, for binaries parsing it
+/// along with their own arguments.
+pub fn run_with<S: Solution>(args: Args) -> Result<()> {
+    for_each_input(&args, |input, label| {
+        let solved = bench::<S>(input, 1)?;
+        match args.format {
+            Format::Text => print_answers(&solved.answers),
+            Format::Json => print_json(S::DAY, label, &solved)?,
+        }
+        Ok(())
+    })
+}
+
+/// Calls `answer` with each input given on the command line in turn, and
+/// the name to label its records with when there are several. Text answers
+/// of several inputs are printed under their names.
+pub fn for_each_input(
+    args: &Args,
+    mut answer: impl FnMut(&str, Option<&str>) -> Result<()>,
+) -> Result<()> {
+    let several = args.inputs.len() > 1;
+    for (i, path) in args.inputs.iter().enumerate() {
+        let input = read_input(path)?;
+        if several && args.format == Format::Text {
+            if i > 0 {
+                println!();
+            }
+            println!("{}:", path);
+        }
+        answer(&input, several.then_some(path.as_str())).map_err(|e| with_file(e, path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<i32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().sum::<i32>().into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().product::<i32>().into())
+        }
+    }
+
+    #[test]
+    fn test_solve() -> Result<()> {
+        assert_eq!(
+            solve::<Sum>("1 2 3 4")?,
+            [Answer::Number(10), Answer::Number(24)]
+        );
+        assert!(solve::<Sum>("1 x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bench() -> Result<()> {
+        let Solved { answers, timings } = bench::<Sum>("1 2 3 4", 3)?;
+        assert_eq!(answers, [Answer::Number(10), Answer::Number(24)]);
+        assert_eq!(timings.parse.len(), 3);
+        assert_eq!(timings.part1.len(), 3);
+        assert_eq!(timings.part2.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records() -> Result<()> {
+        let solved = Solved {
+            answers: [
+                Answer::Number(10),
+                Answer::Grid(vec![vec![true, false], vec![false, true]]),
+            ],
+            timings: Timings {
+                parse: vec![Duration::from_nanos(1)],
+                part1: vec![Duration::from_nanos(3), Duration::from_nanos(2)],
+                part2: vec![Duration::from_nanos(4)],
+            },
+        };
+        let [fst, snd] = solved.records(13);
+        assert_eq!(
+            serde_json::to_string(&fst)?,
+            r#"{"day":13,"part":1,"answer":10,"elapsed":3}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&snd)?,
+            r##"{"day":13,"part":2,"answer":["#.",".#"],"elapsed":4}"##
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(
+            Answer::Grid(vec![vec![true, false], vec![false, true]]).to_string(),
+            "\n#.\n.#"
+        );
+    }
+}